@@ -0,0 +1,542 @@
+// `#[derive(Fail)]` places its impls inside a named constant, which this lint flags.
+#![allow(non_local_definitions)]
+
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use url::{form_urlencoded, Url};
+
+use super::jwt::{self, HmacSigningKey, JwsSigningKey, JwtSigningError};
+use super::prelude::*;
+use super::{
+    BackchannelAuthenticationUrl, CertificatePem, ClientId, ClientSecret, DeviceAuthorizationUrl,
+    IntrospectionUrl, RevocationUrl, TokenUrl,
+};
+
+///
+/// Error encountered while authenticating the client.
+///
+#[derive(Debug, Fail)]
+pub enum ClientAuthenticationError {
+    ///
+    /// A client assertion could not be signed.
+    ///
+    #[fail(display = "Failed to sign client assertion: {}", _0)]
+    Jwt(#[cause] JwtSigningError),
+    ///
+    /// Some other type of error occurred (e.g., a required credential is missing).
+    ///
+    #[fail(display = "Client authentication failed: {}", _0)]
+    Other(String),
+}
+
+///
+/// Authorization server endpoint to which a client authentication method applies.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Endpoint {
+    ///
+    /// The token endpoint, used by all of the `exchange_*` methods that return access tokens.
+    ///
+    Token,
+    ///
+    /// The device authorization endpoint used by the
+    /// [Device Authorization Grant](https://tools.ietf.org/html/rfc8628).
+    ///
+    DeviceAuthorization,
+    ///
+    /// The backchannel authentication endpoint used by the
+    /// [CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html)
+    /// flow.
+    ///
+    BackchannelAuthentication,
+    ///
+    /// The token introspection endpoint (see [RFC 7662](https://tools.ietf.org/html/rfc7662)).
+    ///
+    Introspection,
+    ///
+    /// The token revocation endpoint (see [RFC 7009](https://tools.ietf.org/html/rfc7009)).
+    ///
+    Revocation,
+}
+
+///
+/// Information about the client and the request being authenticated, passed to
+/// `ClientAuthentication::authenticate`.
+///
+#[derive(Debug)]
+pub struct ClientAuthenticationContext<'a> {
+    pub(crate) client_id: &'a ClientId,
+    pub(crate) client_secret: Option<&'a ClientSecret>,
+    pub(crate) token_url: Option<&'a TokenUrl>,
+    pub(crate) endpoint: Endpoint,
+    pub(crate) url: &'a Url,
+    pub(crate) client_certificate: Option<&'a CertificatePem>,
+}
+
+impl<'a> ClientAuthenticationContext<'a> {
+    ///
+    /// The client identifier.
+    ///
+    pub fn client_id(&self) -> &ClientId {
+        self.client_id
+    }
+    ///
+    /// The client secret, if any.
+    ///
+    pub fn client_secret(&self) -> Option<&ClientSecret> {
+        self.client_secret
+    }
+    ///
+    /// The authorization server's token endpoint, if any.
+    ///
+    pub fn token_url(&self) -> Option<&TokenUrl> {
+        self.token_url
+    }
+    ///
+    /// The endpoint to which the request is being sent.
+    ///
+    pub fn endpoint(&self) -> Endpoint {
+        self.endpoint
+    }
+    ///
+    /// The URL to which the request is being sent.
+    ///
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+    ///
+    /// The client certificate presented for mutual TLS (see `Client::set_client_certificate`),
+    /// if any.
+    ///
+    pub fn client_certificate(&self) -> Option<&CertificatePem> {
+        self.client_certificate
+    }
+}
+
+///
+/// Method used by the client to authenticate to the authorization server (see
+/// [Section 2.3 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3)).
+///
+/// Client authentication methods add credentials to each outgoing request by appending form
+/// parameters and/or HTTP headers. This trait may be implemented to support authentication
+/// methods beyond those provided by this module, such as methods registered by particular
+/// authorization servers.
+///
+pub trait ClientAuthentication: Send + Sync {
+    ///
+    /// Adds the client's credentials to an outgoing request by appending to its form `params`
+    /// and/or `headers`.
+    ///
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError>;
+}
+
+impl Debug for dyn ClientAuthentication {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "ClientAuthentication")
+    }
+}
+
+///
+/// Authenticates using HTTP Basic authentication with the client ID and client secret
+/// (`client_secret_basic`), as recommended in
+/// [Section 2.3.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3.1).
+///
+/// This is the default client authentication method.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClientSecretBasic;
+
+impl ClientAuthentication for ClientSecretBasic {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        _params: &mut Vec<(String, String)>,
+        headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        // Section 2.3.1 of RFC 6749 requires separately url-encoding the id and secret
+        // before using them as HTTP Basic auth username and password. Note that this is
+        // not standard for ordinary Basic auth, so HTTP clients won't do it for us.
+        let urlencoded_id: String =
+            form_urlencoded::byte_serialize(context.client_id().as_bytes()).collect();
+        let urlencoded_secret: String = context
+            .client_secret()
+            .map(|secret| form_urlencoded::byte_serialize(secret.secret().as_bytes()).collect())
+            .unwrap_or_default();
+        let b64_credential = base64::encode(&format!("{}:{}", urlencoded_id, urlencoded_secret));
+        headers.append(
+            AUTHORIZATION,
+            // The base64 alphabet only contains valid header value characters.
+            HeaderValue::from_str(&format!("Basic {}", b64_credential)).unwrap(),
+        );
+
+        Ok(())
+    }
+}
+
+///
+/// Authenticates by including the client ID and client secret (if any) in the request body
+/// (`client_secret_post`).
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClientSecretPost;
+
+impl ClientAuthentication for ClientSecretPost {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        params.push(("client_id".to_string(), context.client_id().to_string()));
+        if let Some(client_secret) = context.client_secret() {
+            params.push((
+                "client_secret".to_string(),
+                client_secret.secret().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Identifies a public client by including only the client ID in the request body (`none`),
+/// without any client authentication. Any configured client secret is not sent.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoAuthentication;
+
+impl ClientAuthentication for NoAuthentication {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        params.push(("client_id".to_string(), context.client_id().to_string()));
+
+        Ok(())
+    }
+}
+
+// Client assertion type for JWTs used for client authentication (see
+// https://tools.ietf.org/html/rfc7523#section-2.2).
+const JWT_BEARER_CLIENT_ASSERTION_TYPE: &str =
+    "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+///
+/// Authenticates using a JWT signed with HMAC SHA-256 (`HS256`) keyed by the client secret
+/// (`client_secret_jwt`), as defined in
+/// [Section 9 of OpenID Connect Core](https://openid.net/specs/openid-connect-core-1_0.html#ClientAuthentication)
+/// and [RFC 7523](https://tools.ietf.org/html/rfc7523#section-2.2).
+///
+/// A fresh assertion with a random `jti` is generated each time a request is sent, including
+/// retries and token endpoint polls. Its `iss` and `sub` claims contain the client ID, and its
+/// `aud` claim contains the token endpoint URL (or the request URL if the client has no token
+/// endpoint). The client secret itself is never sent.
+///
+#[derive(Clone, Debug)]
+pub struct ClientSecretJwt {
+    expires_in: Duration,
+}
+
+impl ClientSecretJwt {
+    ///
+    /// Creates a client authentication method whose assertions expire 5 minutes after they are
+    /// issued unless `set_expires_in` is called.
+    ///
+    pub fn new() -> Self {
+        ClientSecretJwt {
+            expires_in: Duration::from_secs(300),
+        }
+    }
+
+    ///
+    /// Sets the lifetime of each assertion, which determines its `exp` (expiration time) claim.
+    ///
+    pub fn set_expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_in = expires_in;
+
+        self
+    }
+}
+
+impl Default for ClientSecretJwt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientAuthentication for ClientSecretJwt {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        let client_secret = context.client_secret().ok_or_else(|| {
+            ClientAuthenticationError::Other(
+                "client_secret_jwt requires a client secret".to_string(),
+            )
+        })?;
+        let signing_key = HmacSigningKey::hs256(client_secret.secret().as_bytes());
+
+        push_client_assertion(context, &signing_key, self.expires_in, params)
+    }
+}
+
+///
+/// Authenticates using a JWT signed with the client's private key (`private_key_jwt`), as
+/// defined in
+/// [Section 9 of OpenID Connect Core](https://openid.net/specs/openid-connect-core-1_0.html#ClientAuthentication)
+/// and [RFC 7523](https://tools.ietf.org/html/rfc7523#section-2.2).
+///
+/// The assertion is signed using any `JwsSigningKey`, such as the `RS256`, `PS256`, `ES256` and
+/// `EdDSA` keys provided by the `jwt` module when the `jwt` feature is enabled. As with
+/// `ClientSecretJwt`, a fresh assertion with the same claims is signed each time a request is
+/// sent.
+///
+#[derive(Clone, Debug)]
+pub struct PrivateKeyJwt {
+    signing_key: Arc<dyn JwsSigningKey>,
+    expires_in: Duration,
+}
+
+impl PrivateKeyJwt {
+    ///
+    /// Creates a client authentication method whose assertions are signed using `signing_key`
+    /// and expire 5 minutes after they are issued unless `set_expires_in` is called.
+    ///
+    pub fn new<K>(signing_key: K) -> Self
+    where
+        K: JwsSigningKey + 'static,
+    {
+        PrivateKeyJwt {
+            signing_key: Arc::new(signing_key),
+            expires_in: Duration::from_secs(300),
+        }
+    }
+
+    ///
+    /// Sets the lifetime of each assertion, which determines its `exp` (expiration time) claim.
+    ///
+    pub fn set_expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_in = expires_in;
+
+        self
+    }
+}
+
+impl ClientAuthentication for PrivateKeyJwt {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        push_client_assertion(context, &*self.signing_key, self.expires_in, params)
+    }
+}
+
+// Appends a freshly signed client assertion (see https://tools.ietf.org/html/rfc7523#section-3),
+// along with the client ID, to the request parameters.
+fn push_client_assertion(
+    context: &ClientAuthenticationContext,
+    signing_key: &dyn JwsSigningKey,
+    expires_in: Duration,
+    params: &mut Vec<(String, String)>,
+) -> Result<(), ClientAuthenticationError> {
+    let audience = match context.token_url() {
+        Some(token_url) => token_url.to_string(),
+        None => context.url().to_string(),
+    };
+    let claims = jwt::assertion_claims(
+        context.client_id(),
+        context.client_id(),
+        &audience,
+        expires_in,
+    );
+    let assertion = jwt::encode(signing_key, &claims).map_err(ClientAuthenticationError::Jwt)?;
+
+    params.push(("client_id".to_string(), context.client_id().to_string()));
+    params.push((
+        "client_assertion_type".to_string(),
+        JWT_BEARER_CLIENT_ASSERTION_TYPE.to_string(),
+    ));
+    params.push(("client_assertion".to_string(), assertion));
+
+    Ok(())
+}
+
+///
+/// Authenticates using mutual TLS with a client certificate issued by a certificate authority
+/// trusted by the authorization server (`tls_client_auth`), as defined in
+/// [Section 2.1 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-2.1).
+///
+/// The client certificate must be configured via `Client::set_client_certificate`. Only the
+/// client ID is included in the request body.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TlsClientAuth;
+
+impl ClientAuthentication for TlsClientAuth {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        push_tls_client_id(context, "tls_client_auth", params)
+    }
+}
+
+///
+/// Authenticates using mutual TLS with a self-signed client certificate registered with the
+/// authorization server (`self_signed_tls_client_auth`), as defined in
+/// [Section 2.2 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-2.2).
+///
+/// The client certificate must be configured via `Client::set_client_certificate`. Only the
+/// client ID is included in the request body.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SelfSignedTlsClientAuth;
+
+impl ClientAuthentication for SelfSignedTlsClientAuth {
+    fn authenticate(
+        &self,
+        context: &ClientAuthenticationContext,
+        params: &mut Vec<(String, String)>,
+        _headers: &mut HeaderMap,
+    ) -> Result<(), ClientAuthenticationError> {
+        push_tls_client_id(context, "self_signed_tls_client_auth", params)
+    }
+}
+
+// The client certificate itself is presented by the HTTP client during the TLS handshake, so
+// mutual TLS client authentication only identifies the client in the request body.
+fn push_tls_client_id(
+    context: &ClientAuthenticationContext,
+    method: &str,
+    params: &mut Vec<(String, String)>,
+) -> Result<(), ClientAuthenticationError> {
+    if context.client_certificate().is_none() {
+        return Err(ClientAuthenticationError::Other(format!(
+            "{} requires a client certificate",
+            method
+        )));
+    }
+    params.push(("client_id".to_string(), context.client_id().to_string()));
+
+    Ok(())
+}
+
+///
+/// Alternative endpoints used by clients when making requests over mutual TLS, as advertised by
+/// the authorization server's `mtls_endpoint_aliases` metadata (see
+/// [Section 5 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-5)).
+///
+/// This type may be deserialized directly from the `mtls_endpoint_aliases` metadata value.
+/// Endpoints without an alias continue to use the URL configured on the `Client`.
+///
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MtlsEndpointAliases {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_endpoint: Option<TokenUrl>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_authorization_endpoint: Option<DeviceAuthorizationUrl>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backchannel_authentication_endpoint: Option<BackchannelAuthenticationUrl>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    introspection_endpoint: Option<IntrospectionUrl>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revocation_endpoint: Option<RevocationUrl>,
+}
+
+impl MtlsEndpointAliases {
+    ///
+    /// Creates an empty set of endpoint aliases.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Sets the token endpoint alias.
+    ///
+    pub fn set_token_url(mut self, token_url: TokenUrl) -> Self {
+        self.token_endpoint = Some(token_url);
+
+        self
+    }
+
+    ///
+    /// Sets the device authorization endpoint alias.
+    ///
+    pub fn set_device_authorization_url(
+        mut self,
+        device_authorization_url: DeviceAuthorizationUrl,
+    ) -> Self {
+        self.device_authorization_endpoint = Some(device_authorization_url);
+
+        self
+    }
+
+    ///
+    /// Sets the backchannel authentication endpoint alias.
+    ///
+    pub fn set_backchannel_authentication_url(
+        mut self,
+        backchannel_authentication_url: BackchannelAuthenticationUrl,
+    ) -> Self {
+        self.backchannel_authentication_endpoint = Some(backchannel_authentication_url);
+
+        self
+    }
+
+    ///
+    /// Sets the token introspection endpoint alias.
+    ///
+    pub fn set_introspection_url(mut self, introspection_url: IntrospectionUrl) -> Self {
+        self.introspection_endpoint = Some(introspection_url);
+
+        self
+    }
+
+    ///
+    /// Sets the token revocation endpoint alias.
+    ///
+    pub fn set_revocation_url(mut self, revocation_url: RevocationUrl) -> Self {
+        self.revocation_endpoint = Some(revocation_url);
+
+        self
+    }
+
+    ///
+    /// Returns the alias for the given endpoint, if any.
+    ///
+    pub fn url(&self, endpoint: Endpoint) -> Option<&Url> {
+        match endpoint {
+            Endpoint::Token => self.token_endpoint.as_deref(),
+            Endpoint::DeviceAuthorization => self.device_authorization_endpoint.as_deref(),
+            Endpoint::BackchannelAuthentication => {
+                self.backchannel_authentication_endpoint.as_deref()
+            }
+            Endpoint::Introspection => self.introspection_endpoint.as_deref(),
+            Endpoint::Revocation => self.revocation_endpoint.as_deref(),
+        }
+    }
+}
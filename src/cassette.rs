@@ -0,0 +1,230 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::status::StatusCode;
+
+use super::{
+    AsyncHttpClient, HttpClient, HttpClientError, HttpClientFuture, HttpRequest, HttpResponse,
+};
+
+///
+/// HTTP client that forwards each request to another HTTP client and records the exchange to a
+/// JSON cassette file, which may later be served by a `ReplayHttpClient`.
+///
+/// Secrets (client secrets, authorization codes, PKCE code verifiers, passwords, and tokens,
+/// including the `Authorization` header) are replaced by `[redacted]` before being recorded, as
+/// are DPoP proofs in the `DPoP` header, which differ for every request. The cassette file is
+/// rewritten after each successful exchange. Requests that fail with an `HttpClientError` are not
+/// recorded.
+///
+/// This type implements `HttpClient` if the wrapped client implements `HttpClient`, and
+/// `AsyncHttpClient` if the wrapped client implements `AsyncHttpClient`.
+///
+#[derive(Clone, Debug)]
+pub struct RecordingHttpClient<C> {
+    inner: Arc<C>,
+    path: PathBuf,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl<C> RecordingHttpClient<C> {
+    ///
+    /// Creates a client that forwards requests to `inner` and records them to the cassette file
+    /// at `path`, replacing any existing file.
+    ///
+    pub fn new<P: AsRef<Path>>(inner: C, path: P) -> Self {
+        RecordingHttpClient {
+            inner: Arc::new(inner),
+            path: path.as_ref().to_path_buf(),
+            cassette: Arc::new(Mutex::new(Cassette::default())),
+        }
+    }
+
+    fn record(
+        path: &Path,
+        cassette: &Mutex<Cassette>,
+        request: &HttpRequest,
+        response: &HttpResponse,
+    ) -> Result<(), HttpClientError> {
+        let mut cassette = cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request: RecordedRequest::new(request),
+            response: RecordedResponse::new(response),
+        });
+
+        let write_error = |err: &dyn Display| {
+            HttpClientError::Other(format!("Failed to write cassette: {}", err))
+        };
+        let file = File::create(path).map_err(|err| write_error(&err))?;
+        serde_json::to_writer_pretty(file, &*cassette).map_err(|err| write_error(&err))
+    }
+}
+
+impl<C: HttpClient> HttpClient for RecordingHttpClient<C> {
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let response = self.inner.request(request.clone())?;
+        Self::record(&self.path, &self.cassette, &request, &response)?;
+        Ok(response)
+    }
+}
+
+impl<C: AsyncHttpClient + 'static> AsyncHttpClient for RecordingHttpClient<C> {
+    fn request_async(&self, request: HttpRequest) -> HttpClientFuture {
+        let response = self.inner.request_async(request.clone());
+        let path = self.path.clone();
+        let cassette = self.cassette.clone();
+
+        Box::pin(async move {
+            let response = response.await?;
+            Self::record(&path, &cassette, &request, &response)?;
+            Ok(response)
+        })
+    }
+}
+
+///
+/// HTTP client that serves responses from a cassette file recorded by a `RecordingHttpClient`,
+/// without sending any requests over the network.
+///
+/// Recorded responses are served in order. Each request must match the corresponding recorded
+/// request (ignoring any redacted secrets); otherwise, an `HttpClientError::Other` error is
+/// returned.
+///
+#[derive(Clone, Debug)]
+pub struct ReplayHttpClient {
+    interactions: Arc<Vec<Interaction>>,
+    position: Arc<Mutex<usize>>,
+}
+
+impl ReplayHttpClient {
+    ///
+    /// Loads the cassette file at `path`.
+    ///
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let cassette: Cassette = serde_json::from_reader(File::open(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(ReplayHttpClient {
+            interactions: Arc::new(cassette.interactions),
+            position: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    ///
+    /// Returns the number of recorded interactions that have not yet been served.
+    ///
+    pub fn remaining(&self) -> usize {
+        self.interactions.len() - *self.position.lock().unwrap()
+    }
+}
+
+impl HttpClient for ReplayHttpClient {
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let mut position = self.position.lock().unwrap();
+        let interaction = self.interactions.get(*position).ok_or_else(|| {
+            HttpClientError::Other("Cassette has no more recorded interactions".to_string())
+        })?;
+
+        let actual = RecordedRequest::new(&request);
+        if actual != interaction.request {
+            return Err(HttpClientError::Other(format!(
+                "Request does not match recorded interaction {}: expected {:?}, found {:?}",
+                *position, interaction.request, actual
+            )));
+        }
+
+        *position += 1;
+        interaction.response.to_response()
+    }
+}
+
+impl AsyncHttpClient for ReplayHttpClient {
+    fn request_async(&self, request: HttpRequest) -> HttpClientFuture {
+        let response = self.request(request);
+        Box::pin(async move { response })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RecordedRequest {
+    fn new(request: &HttpRequest) -> Self {
+        let redacted = request.redacted();
+        RecordedRequest {
+            method: redacted.method.to_string(),
+            url: redacted.url.to_string(),
+            headers: record_headers(&redacted.headers),
+            body: String::from_utf8_lossy(&redacted.body).into_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedResponse {
+    status_code: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl RecordedResponse {
+    fn new(response: &HttpResponse) -> Self {
+        let redacted = response.redacted();
+        RecordedResponse {
+            status_code: redacted.status_code.as_u16(),
+            headers: record_headers(&redacted.headers),
+            body: String::from_utf8_lossy(&redacted.body).into_owned(),
+        }
+    }
+
+    fn to_response(&self) -> Result<HttpResponse, HttpClientError> {
+        let invalid = |what: &str| HttpClientError::Other(format!("Invalid recorded {}", what));
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid("header name"))?,
+                HeaderValue::from_str(value).map_err(|_| invalid("header value"))?,
+            );
+        }
+
+        Ok(HttpResponse {
+            status_code: StatusCode::from_u16(self.status_code)
+                .map_err(|_| invalid("status code"))?,
+            headers,
+            body: self.body.clone().into_bytes(),
+        })
+    }
+}
+
+fn record_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
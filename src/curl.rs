@@ -0,0 +1,234 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use curl::easy::{Easy, List};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::method::Method;
+use http::status::StatusCode;
+
+use super::prelude::*;
+use super::{HttpClient, HttpClientConfig, HttpClientError, HttpRequest, HttpResponse};
+
+///
+/// Synchronous HTTP client backed by [curl](https://crates.io/crates/curl).
+///
+/// This is the default HTTP client used by `Client` when the `curl` feature is enabled.
+///
+/// Idle curl handles are kept in a pool after each request and reused by subsequent requests,
+/// allowing their open (keep-alive) connections to the authorization server to be reused rather
+/// than paying for a new TCP and TLS handshake each time. The pool is shared by all clones of a
+/// `CurlHttpClient` (including clones of a `Client` using it) and is safe to use from multiple
+/// threads concurrently.
+///
+#[derive(Clone, Debug, Default)]
+pub struct CurlHttpClient {
+    handles: Arc<Mutex<Vec<Easy>>>,
+}
+
+impl CurlHttpClient {
+    ///
+    /// Creates a new HTTP client with an empty connection pool.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl HttpClient for CurlHttpClient {
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let mut easy = self.handles.lock().unwrap().pop().unwrap_or_else(Easy::new);
+        // Clear the options set by any previous request while retaining the handle's open
+        // connections. Handles are only returned to the pool after successful requests.
+        easy.reset();
+        let response = send(&mut easy, request)?;
+
+        self.handles.lock().unwrap().push(easy);
+        Ok(response)
+    }
+}
+
+///
+/// Synchronous HTTP client backed by [curl](https://crates.io/crates/curl).
+///
+/// A new curl handle (and connection) is created for each request. Prefer `CurlHttpClient` when
+/// sending many requests.
+///
+pub fn http_client(request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+    send(&mut Easy::new(), request)
+}
+
+fn send(easy: &mut Easy, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+    easy.url(&request.url.to_string()[..])?;
+    configure(easy, &request.config)?;
+
+    let mut headers = List::new();
+    for (name, value) in &request.headers {
+        let value = value.to_str().map_err(|_| {
+            HttpClientError::Other(format!("Invalid value for request header `{}`", name))
+        })?;
+        headers.append(&format!("{}: {}", name, value))?;
+    }
+    easy.http_headers(headers)?;
+
+    if request.method == Method::POST {
+        easy.post(true)?;
+        // Copying the body allows curl to resend it if a reused connection turns out to have been
+        // closed by the server.
+        easy.post_fields_copy(&request.body)?;
+    } else if request.method != Method::GET {
+        easy.custom_request(request.method.as_str())?;
+    }
+
+    let mut data = Vec::new();
+    let mut response_headers = HeaderMap::new();
+    {
+        let mut transfer = easy.transfer();
+
+        transfer.write_function(|new_data| {
+            data.extend_from_slice(new_data);
+            Ok(new_data.len())
+        })?;
+
+        transfer.header_function(|header_line| {
+            parse_header_line(&mut response_headers, header_line);
+            true
+        })?;
+
+        transfer.perform()?;
+    }
+
+    let status_code = easy.response_code()?;
+
+    Ok(HttpResponse {
+        status_code: StatusCode::from_u16(status_code as u16).map_err(|_| {
+            HttpClientError::Other(format!("Invalid HTTP status code `{}`", status_code))
+        })?,
+        headers: response_headers,
+        body: data,
+    })
+}
+
+// Locations of the system CA bundle on common platforms, which are checked in order when
+// extending curl's default trust store.
+const SYSTEM_CA_BUNDLES: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/pki/ca-trust/extracted/pem/tls-ca-bundle.pem",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/pki/tls/cacert.pem",
+    "/etc/ssl/cert.pem",
+];
+
+fn configure(easy: &mut Easy, config: &HttpClientConfig) -> Result<(), HttpClientError> {
+    if let Some(connect_timeout) = config.connect_timeout() {
+        easy.connect_timeout(connect_timeout)?;
+    }
+    if let Some(timeout) = config.timeout() {
+        easy.timeout(timeout)?;
+    }
+    if let Some(proxy) = config.proxy() {
+        easy.proxy(proxy.url().as_str())?;
+        if let Some((username, password)) = proxy.credentials() {
+            easy.proxy_username(username)?;
+            easy.proxy_password(password.secret())?;
+        }
+    }
+    if !config.no_proxy().is_empty() {
+        easy.noproxy(&config.no_proxy().join(","))?;
+    }
+    if !config.root_certificates().is_empty() || !config.builtin_root_certificates() {
+        // curl accepts a single CA blob, which may contain several concatenated certificates.
+        // The blob replaces curl's default CA bundle, so the system's CA bundle is included in
+        // it unless the built-in root certificates are disabled.
+        let mut bundle = if config.builtin_root_certificates() {
+            system_ca_bundle()?
+        } else {
+            Vec::new()
+        };
+        for certificate in config.root_certificates() {
+            bundle.push(b'\n');
+            bundle.extend_from_slice(certificate);
+        }
+        easy.ssl_cainfo_blob(&bundle)?;
+    }
+    if !config.pinned_public_keys().is_empty() {
+        let pins = config
+            .pinned_public_keys()
+            .iter()
+            .map(|pin| format!("sha256//{}", pin.as_str()))
+            .collect::<Vec<_>>()
+            .join(";");
+        easy.pinned_public_key(&pins)?;
+    }
+    if let Some((certificate, private_key)) = config.client_certificate() {
+        easy.ssl_cert_blob(certificate)?;
+        easy.ssl_cert_type("PEM")?;
+        easy.ssl_key_blob(private_key.secret())?;
+        easy.ssl_key_type("PEM")?;
+    }
+    Ok(())
+}
+
+// Reads the system's CA bundle, honoring the `SSL_CERT_FILE` and `CURL_CA_BUNDLE` environment
+// variables.
+fn system_ca_bundle() -> Result<Vec<u8>, HttpClientError> {
+    let path = ["SSL_CERT_FILE", "CURL_CA_BUNDLE"]
+        .iter()
+        .filter_map(env::var_os)
+        .find(|path| !path.is_empty())
+        .map(Into::into)
+        .or_else(|| {
+            SYSTEM_CA_BUNDLES
+                .iter()
+                .map(Path::new)
+                .find(|path| path.is_file())
+                .map(Path::to_path_buf)
+        })
+        .ok_or_else(|| {
+            HttpClientError::Other(
+                "Failed to locate the system CA bundle; set the `SSL_CERT_FILE` environment \
+                 variable or call `Client::set_builtin_root_certificates(false)`"
+                    .to_string(),
+            )
+        })?;
+    fs::read(&path).map_err(|err| {
+        HttpClientError::Other(format!(
+            "Failed to read the system CA bundle `{}`: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+fn parse_header_line(headers: &mut HeaderMap, header_line: &[u8]) {
+    // Each status line (e.g., following an interim 100 Continue response) begins a new set of
+    // response headers.
+    if header_line.starts_with(b"HTTP/") {
+        headers.clear();
+        return;
+    }
+
+    let colon_pos = match header_line.iter().position(|&b| b == b':') {
+        Some(colon_pos) => colon_pos,
+        // Skip the blank line that terminates the headers, along with any malformed lines.
+        None => return,
+    };
+
+    let name = HeaderName::from_bytes(&header_line[..colon_pos]);
+    let value = HeaderValue::from_bytes(header_line[colon_pos + 1..].trim_ascii());
+    if let (Ok(name), Ok(value)) = (name, value) {
+        headers.append(name, value);
+    }
+}
+
+impl From<curl::Error> for HttpClientError {
+    fn from(err: curl::Error) -> Self {
+        if err.is_operation_timedout() {
+            HttpClientError::Timeout
+        } else {
+            HttpClientError::Transport(err.into())
+        }
+    }
+}
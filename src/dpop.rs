@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use http::header::HeaderName;
+use http::method::Method;
+use http::status::StatusCode;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use super::jwt::{self, JwsSigningKey, JwtSigningError};
+use super::prelude::*;
+use super::{AccessToken, HttpResponse};
+
+///
+/// Name of the HTTP header containing a DPoP proof.
+///
+pub const DPOP: HeaderName = HeaderName::from_static("dpop");
+
+///
+/// Name of the HTTP header containing a nonce provided by the server for inclusion in
+/// subsequent DPoP proofs.
+///
+pub const DPOP_NONCE: HeaderName = HeaderName::from_static("dpop-nonce");
+
+///
+/// Key held by the client for demonstrating proof-of-possession of DPoP-bound access tokens (see
+/// [RFC 9449](https://tools.ietf.org/html/rfc9449)).
+///
+/// When configured via `Client::set_dpop_key`, a freshly signed proof is attached to every
+/// request sent to the token endpoint in the `DPoP` header. Proofs for requests to resource
+/// servers are generated using `proof`.
+///
+/// Nonces provided by servers via the `DPoP-Nonce` header are remembered separately for each
+/// origin and included in subsequent proofs for that origin. Clones of a `DpopKey` share the
+/// same nonces.
+///
+#[derive(Clone, Debug)]
+pub struct DpopKey {
+    signing_key: Arc<dyn JwsSigningKey>,
+    public_jwk: Map<String, Value>,
+    jwk_thumbprint: String,
+    nonces: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl DpopKey {
+    ///
+    /// Creates a DPoP key holder that signs proofs using the given asymmetric key.
+    ///
+    /// # Failures
+    /// Returns `JwtSigningError::InvalidKey` if the key does not provide a public JWK (e.g., an
+    /// HMAC key) or if its JWK thumbprint cannot be computed.
+    ///
+    pub fn new<K>(signing_key: K) -> Result<Self, JwtSigningError>
+    where
+        K: JwsSigningKey + 'static,
+    {
+        let public_jwk = signing_key.public_jwk().ok_or_else(|| {
+            JwtSigningError::InvalidKey("DPoP proofs require an asymmetric key".to_string())
+        })?;
+        let jwk_thumbprint = jwk_thumbprint(&public_jwk)?;
+
+        Ok(DpopKey {
+            signing_key: Arc::new(signing_key),
+            public_jwk,
+            jwk_thumbprint,
+            nonces: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    ///
+    /// The public key included in the `jwk` header of each proof.
+    ///
+    pub fn public_jwk(&self) -> &Map<String, Value> {
+        &self.public_jwk
+    }
+
+    ///
+    /// Base64url-encoded [JWK SHA-256 thumbprint](https://tools.ietf.org/html/rfc7638) of the
+    /// public key, which appears in the `cnf.jkt` claim of DPoP-bound access tokens.
+    ///
+    pub fn jwk_thumbprint(&self) -> String {
+        self.jwk_thumbprint.clone()
+    }
+
+    ///
+    /// Generates a signed proof for an HTTP request with the given method and URL.
+    ///
+    /// When accessing a protected resource, `access_token` must be the DPoP-bound access token
+    /// being presented, whose hash is included in the proof's `ath` claim. The token itself is
+    /// sent in an `Authorization: DPoP <token>` header.
+    ///
+    pub fn proof(
+        &self,
+        method: &Method,
+        url: &Url,
+        access_token: Option<&AccessToken>,
+    ) -> Result<String, JwtSigningError> {
+        let mut header = Map::new();
+        header.insert("typ".to_string(), Value::from("dpop+jwt"));
+        header.insert("jwk".to_string(), Value::from(self.public_jwk.clone()));
+
+        // The `htu` claim excludes the query and fragment parts of the URL.
+        let mut htu = url.clone();
+        htu.set_query(None);
+        htu.set_fragment(None);
+
+        let mut claims = Map::new();
+        claims.insert("jti".to_string(), Value::from(jwt::random_jti()));
+        claims.insert("htm".to_string(), Value::from(method.as_str()));
+        claims.insert("htu".to_string(), Value::from(htu.as_str()));
+        claims.insert("iat".to_string(), Value::from(jwt::now()));
+        if let Some(nonce) = self.nonce(url) {
+            claims.insert("nonce".to_string(), Value::from(nonce));
+        }
+        if let Some(access_token) = access_token {
+            claims.insert(
+                "ath".to_string(),
+                Value::from(base64::encode_config(
+                    &Sha256::digest(access_token.secret().as_bytes()),
+                    base64::URL_SAFE_NO_PAD,
+                )),
+            );
+        }
+
+        jwt::encode_with_header(&*self.signing_key, header, &claims)
+    }
+
+    ///
+    /// Returns the most recent nonce provided by the server at the given URL's origin, if any.
+    ///
+    pub fn nonce(&self, url: &Url) -> Option<String> {
+        self.nonces
+            .lock()
+            .unwrap()
+            .get(&url.origin().ascii_serialization())
+            .cloned()
+    }
+
+    ///
+    /// Remembers a nonce provided by the server at the given URL's origin (e.g., via the
+    /// `DPoP-Nonce` header of a resource server response), which is included in subsequent
+    /// proofs for that origin.
+    ///
+    pub fn set_nonce(&self, url: &Url, nonce: String) {
+        self.nonces
+            .lock()
+            .unwrap()
+            .insert(url.origin().ascii_serialization(), nonce);
+    }
+
+    // Remembers any nonce provided in the response to a request sent to the given URL, and returns
+    // whether the server rejected the request because it requires a (new) nonce, in which case
+    // the request should be sent again with a fresh proof that includes the nonce (see
+    // https://tools.ietf.org/html/rfc9449#section-8).
+    pub(crate) fn handle_response(&self, url: &Url, http_response: &HttpResponse) -> bool {
+        let nonce = match http_response
+            .headers
+            .get(DPOP_NONCE)
+            .and_then(|nonce| nonce.to_str().ok())
+        {
+            Some(nonce) => nonce.to_string(),
+            None => return false,
+        };
+        self.set_nonce(url, nonce);
+
+        http_response.status_code == StatusCode::BAD_REQUEST
+            && serde_json::from_slice::<Value>(&http_response.body)
+                .ok()
+                .is_some_and(|body| body["error"] == "use_dpop_nonce")
+    }
+}
+
+///
+/// Computes the base64url-encoded [JWK SHA-256 thumbprint](https://tools.ietf.org/html/rfc7638)
+/// of a public key.
+///
+/// Only the members required for the key's `kty` are hashed (`crv`, `kty`, `x` and `y` for `EC`
+/// keys, `crv`, `kty` and `x` for `OKP` keys, and `e`, `kty` and `n` for `RSA` keys), so other
+/// members such as `kid` and `alg` do not affect the thumbprint.
+///
+/// # Failures
+/// Returns `JwtSigningError::InvalidKey` if the key type is unsupported or a required member is
+/// missing or not a string.
+///
+pub fn jwk_thumbprint(jwk: &Map<String, Value>) -> Result<String, JwtSigningError> {
+    let members: &[&str] = match jwk.get("kty").and_then(Value::as_str) {
+        Some("EC") => &["crv", "kty", "x", "y"],
+        Some("OKP") => &["crv", "kty", "x"],
+        Some("RSA") => &["e", "kty", "n"],
+        kty => {
+            return Err(JwtSigningError::InvalidKey(format!(
+                "Unsupported JWK key type: {:?}",
+                kty
+            )))
+        }
+    };
+
+    // Section 3.2 of RFC 7638 requires the members to appear in lexicographic order without
+    // whitespace, so the JSON is built explicitly rather than relying on the ordering of `Map`
+    // (which preserves insertion order when serde_json's `preserve_order` feature is enabled).
+    let mut json = String::from("{");
+    for (i, name) in members.iter().enumerate() {
+        let value = jwk.get(*name).and_then(Value::as_str).ok_or_else(|| {
+            JwtSigningError::InvalidKey(format!("JWK is missing the `{}` parameter", name))
+        })?;
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str(&Value::from(*name).to_string());
+        json.push(':');
+        json.push_str(&Value::from(value).to_string());
+    }
+    json.push('}');
+
+    Ok(base64::encode_config(
+        &Sha256::digest(json.as_bytes()),
+        base64::URL_SAFE_NO_PAD,
+    ))
+}
@@ -0,0 +1,167 @@
+// `#[derive(Fail)]` places its impls inside a named constant, which this lint flags.
+#![allow(non_local_definitions)]
+
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::{thread_rng, Rng};
+use serde_json::{Map, Value};
+
+pub use self::hmac_key::HmacSigningKey;
+#[cfg(feature = "jwt")]
+pub use self::keys::{EcSigningKey, Ed25519SigningKey, RsaSigningKey};
+
+mod hmac_key;
+#[cfg(feature = "jwt")]
+mod keys;
+
+///
+/// Error encountered while signing a JSON Web Token.
+///
+#[derive(Debug, Fail)]
+pub enum JwtSigningError {
+    ///
+    /// The signing key could not be parsed or is unsuitable for the signing algorithm.
+    ///
+    #[fail(display = "Invalid signing key: {}", _0)]
+    InvalidKey(String),
+    ///
+    /// An error occurred while computing the signature.
+    ///
+    #[fail(display = "Failed to sign JWT: {}", _0)]
+    Signing(String),
+}
+
+///
+/// Key used for signing JSON Web Tokens (e.g., assertions used as authorization grants).
+///
+/// This trait may be implemented to sign tokens using keys that are not directly accessible to
+/// the application, such as those stored in a hardware security module or a cloud key
+/// management service.
+///
+pub trait JwsSigningKey: Send + Sync {
+    ///
+    /// JWS algorithm identifier included in the JWT's `alg` header (e.g., `RS256`).
+    ///
+    fn algorithm(&self) -> &str;
+
+    ///
+    /// Key ID included in the JWT's `kid` header, if any.
+    ///
+    fn key_id(&self) -> Option<&str> {
+        None
+    }
+
+    ///
+    /// Base64url-encoded SHA-256 thumbprint of the DER encoding of the X.509 certificate
+    /// corresponding to this key, included in the JWT's `x5t#S256` header, if any.
+    ///
+    fn x509_sha256_thumbprint(&self) -> Option<&str> {
+        None
+    }
+
+    ///
+    /// Public key corresponding to this key in [JSON Web Key](https://tools.ietf.org/html/rfc7517)
+    /// format, if this is an asymmetric key. Only the members required for computing the key's
+    /// [thumbprint](https://tools.ietf.org/html/rfc7638#section-3.2) should be included.
+    ///
+    /// This is required for keys used to sign DPoP proofs, which embed the public key.
+    ///
+    fn public_jwk(&self) -> Option<Map<String, Value>> {
+        None
+    }
+
+    ///
+    /// Signs the given message (the JWS signing input), returning the raw signature bytes.
+    ///
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JwtSigningError>;
+}
+
+impl Debug for dyn JwsSigningKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "JwsSigningKey({})", self.algorithm())
+    }
+}
+
+// Returns the registered claims common to JWTs used for OAuth 2.0 client authentication and
+// authorization grants (see https://tools.ietf.org/html/rfc7523#section-3), including a random
+// `jti` and an `exp` of `expires_in` from now (saturating at the largest representable time).
+pub(crate) fn assertion_claims(
+    issuer: &str,
+    subject: &str,
+    audience: &str,
+    expires_in: Duration,
+) -> Map<String, Value> {
+    let issued_at = now();
+
+    let mut claims = Map::new();
+    claims.insert("iss".to_string(), Value::from(issuer));
+    claims.insert("sub".to_string(), Value::from(subject));
+    claims.insert("aud".to_string(), Value::from(audience));
+    claims.insert(
+        "exp".to_string(),
+        Value::from(issued_at.saturating_add(expires_in.as_secs())),
+    );
+    claims.insert("iat".to_string(), Value::from(issued_at));
+    claims.insert("jti".to_string(), Value::from(random_jti()));
+    claims
+}
+
+// Returns the current time in seconds since the Unix epoch, as used by the `iat` and `exp`
+// claims.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// Returns a random, base64url-encoded token identifier for the `jti` claim.
+pub(crate) fn random_jti() -> String {
+    let random_bytes: Vec<u8> = (0..16).map(|_| thread_rng().gen::<u8>()).collect();
+    base64::encode_config(&random_bytes, base64::URL_SAFE_NO_PAD)
+}
+
+// Encodes and signs a JWT in JWS compact serialization.
+pub(crate) fn encode(
+    signing_key: &dyn JwsSigningKey,
+    claims: &Map<String, Value>,
+) -> Result<String, JwtSigningError> {
+    let mut header = Map::new();
+    header.insert("typ".to_string(), Value::from("JWT"));
+    if let Some(key_id) = signing_key.key_id() {
+        header.insert("kid".to_string(), Value::from(key_id));
+    }
+    if let Some(thumbprint) = signing_key.x509_sha256_thumbprint() {
+        header.insert("x5t#S256".to_string(), Value::from(thumbprint));
+    }
+
+    encode_with_header(signing_key, header, claims)
+}
+
+// Encodes and signs a JWT in JWS compact serialization using the given header, to which the
+// `alg` header is added.
+pub(crate) fn encode_with_header(
+    signing_key: &dyn JwsSigningKey,
+    mut header: Map<String, Value>,
+    claims: &Map<String, Value>,
+) -> Result<String, JwtSigningError> {
+    header.insert("alg".to_string(), Value::from(signing_key.algorithm()));
+
+    let signing_input = format!("{}.{}", base64_url_json(&header), base64_url_json(claims));
+    let signature = signing_key.sign(signing_input.as_bytes())?;
+
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        base64::encode_config(&signature, base64::URL_SAFE_NO_PAD)
+    ))
+}
+
+fn base64_url_json(value: &Map<String, Value>) -> String {
+    base64::encode_config(
+        &serde_json::to_vec(value).expect("failed to serialize JSON"),
+        base64::URL_SAFE_NO_PAD,
+    )
+}
@@ -0,0 +1,60 @@
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Formatter};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::{JwsSigningKey, JwtSigningError};
+
+///
+/// Symmetric key for signing JWTs using HMAC with SHA-256 (`HS256`).
+///
+#[derive(Clone)]
+pub struct HmacSigningKey {
+    secret: Vec<u8>,
+    key_id: Option<String>,
+}
+
+impl HmacSigningKey {
+    ///
+    /// Creates an `HS256` signing key from the given shared secret.
+    ///
+    pub fn hs256(secret: &[u8]) -> Self {
+        HmacSigningKey {
+            secret: secret.to_vec(),
+            key_id: None,
+        }
+    }
+
+    ///
+    /// Sets the key ID included in the JWT's `kid` header.
+    ///
+    pub fn set_key_id(mut self, key_id: String) -> Self {
+        self.key_id = Some(key_id);
+
+        self
+    }
+}
+
+impl JwsSigningKey for HmacSigningKey {
+    fn algorithm(&self) -> &str {
+        "HS256"
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JwtSigningError> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret)
+            .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+        mac.update(message);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+}
+
+impl Debug for HmacSigningKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "HmacSigningKey([redacted])")
+    }
+}
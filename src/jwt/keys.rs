@@ -0,0 +1,418 @@
+use std::convert::TryFrom;
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Formatter};
+
+use ed25519_dalek::SigningKey as Ed25519Key;
+use p256::ecdsa::{Signature as EcdsaSignature, SigningKey as EcdsaKey};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::rand_core::OsRng;
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Signer};
+use rsa::traits::PublicKeyParts;
+use rsa::{BigUint, RsaPrivateKey};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+
+use super::{JwsSigningKey, JwtSigningError};
+
+///
+/// RSA private key for signing JWTs using either RSASSA-PKCS1-v1_5 with SHA-256 (`RS256`) or
+/// RSASSA-PSS with SHA-256 (`PS256`).
+///
+/// # Security
+/// Signing is implemented by the [rsa](https://crates.io/crates/rsa) crate, which is affected by
+/// the Marvin attack ([RUSTSEC-2023-0071](https://rustsec.org/advisories/RUSTSEC-2023-0071)):
+/// its non-constant-time operations may allow an attacker who can observe signing times over the
+/// network to recover the private key. Prefer `EcSigningKey` or `Ed25519SigningKey` when the
+/// authorization server supports them, or avoid signing with this key where timing information
+/// is observable by untrusted parties.
+///
+#[derive(Clone)]
+pub struct RsaSigningKey {
+    key: RsaPrivateKey,
+    pss: bool,
+    key_id: Option<String>,
+    x509_sha256_thumbprint: Option<String>,
+}
+
+impl RsaSigningKey {
+    ///
+    /// Parses an `RS256` signing key from a PEM-encoded PKCS#8 (`BEGIN PRIVATE KEY`) or PKCS#1
+    /// (`BEGIN RSA PRIVATE KEY`) RSA private key.
+    ///
+    pub fn rs256_from_pem(pem: &str) -> Result<Self, JwtSigningError> {
+        Self::from_pem(pem, false)
+    }
+
+    ///
+    /// Parses a `PS256` signing key from a PEM-encoded PKCS#8 (`BEGIN PRIVATE KEY`) or PKCS#1
+    /// (`BEGIN RSA PRIVATE KEY`) RSA private key.
+    ///
+    pub fn ps256_from_pem(pem: &str) -> Result<Self, JwtSigningError> {
+        Self::from_pem(pem, true)
+    }
+
+    ///
+    /// Parses an `RS256` signing key from an RSA private key in
+    /// [JSON Web Key](https://tools.ietf.org/html/rfc7517) format. The key's `kid` and
+    /// `x5t#S256` parameters, if present, are included in the JWT header.
+    ///
+    pub fn rs256_from_jwk(jwk: &str) -> Result<Self, JwtSigningError> {
+        Self::from_jwk(jwk, false)
+    }
+
+    ///
+    /// Parses a `PS256` signing key from an RSA private key in
+    /// [JSON Web Key](https://tools.ietf.org/html/rfc7517) format. The key's `kid` and
+    /// `x5t#S256` parameters, if present, are included in the JWT header.
+    ///
+    pub fn ps256_from_jwk(jwk: &str) -> Result<Self, JwtSigningError> {
+        Self::from_jwk(jwk, true)
+    }
+
+    ///
+    /// Sets the key ID included in the JWT's `kid` header.
+    ///
+    pub fn set_key_id(mut self, key_id: String) -> Self {
+        self.key_id = Some(key_id);
+
+        self
+    }
+
+    ///
+    /// Sets the certificate thumbprint included in the JWT's `x5t#S256` header.
+    ///
+    pub fn set_x509_sha256_thumbprint(mut self, thumbprint: String) -> Self {
+        self.x509_sha256_thumbprint = Some(thumbprint);
+
+        self
+    }
+
+    fn from_pem(pem: &str, pss: bool) -> Result<Self, JwtSigningError> {
+        let key = RsaPrivateKey::from_pkcs8_pem(pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+            .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+
+        Ok(RsaSigningKey {
+            key,
+            pss,
+            key_id: None,
+            x509_sha256_thumbprint: None,
+        })
+    }
+
+    fn from_jwk(jwk: &str, pss: bool) -> Result<Self, JwtSigningError> {
+        let jwk = parse_jwk(jwk, "RSA")?;
+        let component = |name: &str| jwk_bytes(&jwk, name).map(|b| BigUint::from_bytes_be(&b));
+        let key = RsaPrivateKey::from_components(
+            component("n")?,
+            component("e")?,
+            component("d")?,
+            vec![component("p")?, component("q")?],
+        )
+        .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+
+        Ok(RsaSigningKey {
+            key,
+            pss,
+            key_id: jwk_string(&jwk, "kid"),
+            x509_sha256_thumbprint: jwk_string(&jwk, "x5t#S256"),
+        })
+    }
+}
+
+impl JwsSigningKey for RsaSigningKey {
+    fn algorithm(&self) -> &str {
+        if self.pss {
+            "PS256"
+        } else {
+            "RS256"
+        }
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn x509_sha256_thumbprint(&self) -> Option<&str> {
+        self.x509_sha256_thumbprint.as_deref()
+    }
+
+    fn public_jwk(&self) -> Option<Map<String, Value>> {
+        let mut jwk = Map::new();
+        jwk.insert("kty".to_string(), Value::from("RSA"));
+        jwk.insert("n".to_string(), base64_url(&self.key.n().to_bytes_be()));
+        jwk.insert("e".to_string(), base64_url(&self.key.e().to_bytes_be()));
+        Some(jwk)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JwtSigningError> {
+        let signature = if self.pss {
+            rsa::pss::BlindedSigningKey::<Sha256>::new(self.key.clone())
+                .try_sign_with_rng(&mut OsRng, message)
+                .map(|signature| signature.to_vec())
+        } else {
+            // Signing with an RNG enables blinding, which mitigates timing side channels.
+            rsa::pkcs1v15::SigningKey::<Sha256>::new(self.key.clone())
+                .try_sign_with_rng(&mut OsRng, message)
+                .map(|signature| signature.to_vec())
+        };
+        signature.map_err(|err| JwtSigningError::Signing(err.to_string()))
+    }
+}
+
+impl Debug for RsaSigningKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "RsaSigningKey([redacted])")
+    }
+}
+
+///
+/// Elliptic curve private key for signing JWTs using ECDSA with the P-256 curve and SHA-256
+/// (`ES256`).
+///
+#[derive(Clone)]
+pub struct EcSigningKey {
+    key: EcdsaKey,
+    key_id: Option<String>,
+    x509_sha256_thumbprint: Option<String>,
+}
+
+impl EcSigningKey {
+    ///
+    /// Parses an `ES256` signing key from a PEM-encoded PKCS#8 (`BEGIN PRIVATE KEY`) or SEC1
+    /// (`BEGIN EC PRIVATE KEY`) P-256 private key.
+    ///
+    pub fn es256_from_pem(pem: &str) -> Result<Self, JwtSigningError> {
+        let key = EcdsaKey::from_pkcs8_pem(pem)
+            .or_else(|_| p256::SecretKey::from_sec1_pem(pem).map(EcdsaKey::from))
+            .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+
+        Ok(EcSigningKey {
+            key,
+            key_id: None,
+            x509_sha256_thumbprint: None,
+        })
+    }
+
+    ///
+    /// Parses an `ES256` signing key from a P-256 private key in
+    /// [JSON Web Key](https://tools.ietf.org/html/rfc7517) format. The key's `kid` and
+    /// `x5t#S256` parameters, if present, are included in the JWT header.
+    ///
+    pub fn es256_from_jwk(jwk: &str) -> Result<Self, JwtSigningError> {
+        let jwk = parse_jwk(jwk, "EC")?;
+        check_jwk_curve(&jwk, "P-256")?;
+        let key = EcdsaKey::from_slice(&jwk_bytes(&jwk, "d")?)
+            .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+
+        Ok(EcSigningKey {
+            key,
+            key_id: jwk_string(&jwk, "kid"),
+            x509_sha256_thumbprint: jwk_string(&jwk, "x5t#S256"),
+        })
+    }
+
+    ///
+    /// Sets the key ID included in the JWT's `kid` header.
+    ///
+    pub fn set_key_id(mut self, key_id: String) -> Self {
+        self.key_id = Some(key_id);
+
+        self
+    }
+
+    ///
+    /// Sets the certificate thumbprint included in the JWT's `x5t#S256` header.
+    ///
+    pub fn set_x509_sha256_thumbprint(mut self, thumbprint: String) -> Self {
+        self.x509_sha256_thumbprint = Some(thumbprint);
+
+        self
+    }
+}
+
+impl JwsSigningKey for EcSigningKey {
+    fn algorithm(&self) -> &str {
+        "ES256"
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn x509_sha256_thumbprint(&self) -> Option<&str> {
+        self.x509_sha256_thumbprint.as_deref()
+    }
+
+    fn public_jwk(&self) -> Option<Map<String, Value>> {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        let mut jwk = Map::new();
+        jwk.insert("kty".to_string(), Value::from("EC"));
+        jwk.insert("crv".to_string(), Value::from("P-256"));
+        jwk.insert("x".to_string(), base64_url(point.x()?));
+        jwk.insert("y".to_string(), base64_url(point.y()?));
+        Some(jwk)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JwtSigningError> {
+        // JWS uses the fixed-size concatenation of R and S rather than a DER-encoded signature.
+        let signature: EcdsaSignature = self
+            .key
+            .try_sign(message)
+            .map_err(|err| JwtSigningError::Signing(err.to_string()))?;
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+impl Debug for EcSigningKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "EcSigningKey([redacted])")
+    }
+}
+
+///
+/// Ed25519 private key for signing JWTs using EdDSA (`EdDSA`).
+///
+#[derive(Clone)]
+pub struct Ed25519SigningKey {
+    key: Ed25519Key,
+    key_id: Option<String>,
+    x509_sha256_thumbprint: Option<String>,
+}
+
+impl Ed25519SigningKey {
+    ///
+    /// Parses an `EdDSA` signing key from a PEM-encoded PKCS#8 (`BEGIN PRIVATE KEY`) Ed25519
+    /// private key.
+    ///
+    pub fn from_pem(pem: &str) -> Result<Self, JwtSigningError> {
+        let key = Ed25519Key::from_pkcs8_pem(pem)
+            .map_err(|err| JwtSigningError::InvalidKey(err.to_string()))?;
+
+        Ok(Ed25519SigningKey {
+            key,
+            key_id: None,
+            x509_sha256_thumbprint: None,
+        })
+    }
+
+    ///
+    /// Parses an `EdDSA` signing key from an Ed25519 private key in
+    /// [JSON Web Key](https://tools.ietf.org/html/rfc8037) format. The key's `kid` and
+    /// `x5t#S256` parameters, if present, are included in the JWT header.
+    ///
+    pub fn from_jwk(jwk: &str) -> Result<Self, JwtSigningError> {
+        let jwk = parse_jwk(jwk, "OKP")?;
+        check_jwk_curve(&jwk, "Ed25519")?;
+        let secret = jwk_bytes(&jwk, "d")?;
+        let secret = <[u8; 32]>::try_from(secret.as_slice()).map_err(|_| {
+            JwtSigningError::InvalidKey("Ed25519 private key must be 32 bytes".to_string())
+        })?;
+
+        Ok(Ed25519SigningKey {
+            key: Ed25519Key::from_bytes(&secret),
+            key_id: jwk_string(&jwk, "kid"),
+            x509_sha256_thumbprint: jwk_string(&jwk, "x5t#S256"),
+        })
+    }
+
+    ///
+    /// Sets the key ID included in the JWT's `kid` header.
+    ///
+    pub fn set_key_id(mut self, key_id: String) -> Self {
+        self.key_id = Some(key_id);
+
+        self
+    }
+
+    ///
+    /// Sets the certificate thumbprint included in the JWT's `x5t#S256` header.
+    ///
+    pub fn set_x509_sha256_thumbprint(mut self, thumbprint: String) -> Self {
+        self.x509_sha256_thumbprint = Some(thumbprint);
+
+        self
+    }
+}
+
+impl JwsSigningKey for Ed25519SigningKey {
+    fn algorithm(&self) -> &str {
+        "EdDSA"
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn x509_sha256_thumbprint(&self) -> Option<&str> {
+        self.x509_sha256_thumbprint.as_deref()
+    }
+
+    fn public_jwk(&self) -> Option<Map<String, Value>> {
+        let mut jwk = Map::new();
+        jwk.insert("kty".to_string(), Value::from("OKP"));
+        jwk.insert("crv".to_string(), Value::from("Ed25519"));
+        jwk.insert(
+            "x".to_string(),
+            base64_url(self.key.verifying_key().as_bytes()),
+        );
+        Some(jwk)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JwtSigningError> {
+        let signature = self
+            .key
+            .try_sign(message)
+            .map_err(|err| JwtSigningError::Signing(err.to_string()))?;
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+impl Debug for Ed25519SigningKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "Ed25519SigningKey([redacted])")
+    }
+}
+
+// Parses a private key in JSON Web Key format (see https://tools.ietf.org/html/rfc7517),
+// requiring the given `kty` (key type).
+fn parse_jwk(jwk: &str, key_type: &str) -> Result<Map<String, Value>, JwtSigningError> {
+    let jwk: Map<String, Value> = serde_json::from_str(jwk)
+        .map_err(|err| JwtSigningError::InvalidKey(format!("Invalid JWK: {}", err)))?;
+    match jwk.get("kty").and_then(Value::as_str) {
+        Some(kty) if kty == key_type => Ok(jwk),
+        kty => Err(JwtSigningError::InvalidKey(format!(
+            "Expected JWK key type `{}`, but found {:?}",
+            key_type, kty
+        ))),
+    }
+}
+
+fn check_jwk_curve(jwk: &Map<String, Value>, curve: &str) -> Result<(), JwtSigningError> {
+    match jwk.get("crv").and_then(Value::as_str) {
+        Some(crv) if crv == curve => Ok(()),
+        crv => Err(JwtSigningError::InvalidKey(format!(
+            "Expected JWK curve `{}`, but found {:?}",
+            curve, crv
+        ))),
+    }
+}
+
+// Returns the value of a base64url-encoded JWK parameter.
+fn jwk_bytes(jwk: &Map<String, Value>, name: &str) -> Result<Vec<u8>, JwtSigningError> {
+    let value = jwk.get(name).and_then(Value::as_str).ok_or_else(|| {
+        JwtSigningError::InvalidKey(format!("JWK is missing the `{}` parameter", name))
+    })?;
+    base64::decode_config(value, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        JwtSigningError::InvalidKey(format!("Invalid JWK `{}` parameter: {}", name, err))
+    })
+}
+
+fn jwk_string(jwk: &Map<String, Value>, name: &str) -> Option<String> {
+    jwk.get(name).and_then(Value::as_str).map(str::to_string)
+}
+
+fn base64_url(bytes: &[u8]) -> Value {
+    Value::from(base64::encode_config(bytes, base64::URL_SAFE_NO_PAD))
+}
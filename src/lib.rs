@@ -202,6 +202,178 @@
 //! # fn main() {}
 //! ```
 //!
+//! # Device Authorization Grant
+//!
+//! Devices lacking a browser or with limited input capabilities (e.g., CLI tools and TVs) may use
+//! the [Device Authorization Grant](https://tools.ietf.org/html/rfc8628). The client first
+//! requests a device code and a user code, then instructs the user to enter the user code at the
+//! verification URL using another device. Meanwhile, the client polls the token endpoint until
+//! the user completes (or denies) authorization.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! extern crate oauth2;
+//! extern crate url;
+//!
+//! use oauth2::prelude::*;
+//! use oauth2::{
+//!     AuthUrl,
+//!     ClientId,
+//!     ClientSecret,
+//!     DeviceAuthorizationUrl,
+//!     Scope,
+//!     TokenUrl
+//! };
+//! use oauth2::basic::BasicClient;
+//! use url::Url;
+//!
+//! # fn err_wrapper() -> Result<(), Box<std::error::Error>> {
+//! let client =
+//!     BasicClient::new(
+//!         ClientId::new("client_id".to_string()),
+//!         Some(ClientSecret::new("client_secret".to_string())),
+//!         AuthUrl::new(Url::parse("http://authorize")?),
+//!         Some(TokenUrl::new(Url::parse("http://token")?))
+//!     )
+//!         .set_device_authorization_url(DeviceAuthorizationUrl::new(Url::parse("http://device")?))
+//!         .add_scope(Scope::new("read".to_string()));
+//!
+//! let details = client.exchange_device_code().expect("Failed to request device code");
+//! println!(
+//!     "Open this URL in your browser:\n{}\nand enter the code: {}",
+//!     details.verification_uri().to_string(),
+//!     details.user_code().secret().to_string()
+//! );
+//!
+//! let token_result = client.exchange_device_access_token(&details);
+//! # Ok(())
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! # Selecting an HTTP client
+//!
+//! Token requests are sent through the `HttpClient` trait, which receives an `HttpRequest` and
+//! returns an `HttpResponse`. By default, `Client` uses the
+//! [curl](https://crates.io/crates/curl)-based implementation in the `curl` module, which is
+//! enabled by the `curl` cargo feature (on by default). To build this crate without linking
+//! libcurl, disable the default features and supply your own HTTP client via
+//! `Client::set_http_client`. Any function or closure of the form
+//! `Fn(HttpRequest) -> Result<HttpResponse, HttpClientError>` implements `HttpClient`.
+//!
+//! Blocking and async HTTP clients backed by [reqwest](https://crates.io/crates/reqwest) are
+//! available in the `reqwest` module by enabling one of the following cargo features:
+//!
+//!  * `rustls-tls`: reqwest using [rustls](https://crates.io/crates/rustls) for TLS, which
+//!    requires neither libcurl nor OpenSSL when combined with `default-features = false`.
+//!  * `native-tls`: reqwest using the platform's native TLS implementation.
+//!  * `reqwest`: reqwest without TLS support (plain HTTP only).
+//!
+//! # Signing keys
+//!
+//! JWTs, such as the assertions used by the JWT Bearer grant and by `private_key_jwt` client
+//! authentication, are signed using a `jwt::JwsSigningKey`. The `HS256` key
+//! `jwt::HmacSigningKey` and the `auth::ClientSecretJwt` client authentication method are always
+//! available. The asymmetric keys `jwt::RsaSigningKey` (`RS256`/`PS256`), `jwt::EcSigningKey`
+//! (`ES256`) and `jwt::Ed25519SigningKey` (`EdDSA`) exist only when the `jwt` cargo feature (off
+//! by default) is enabled. Without it, asymmetric signing (including DPoP proofs) requires a
+//! custom `JwsSigningKey` implementation, e.g., one backed by a hardware security module.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate http;
+//! extern crate oauth2;
+//! extern crate url;
+//!
+//! use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+//! use http::status::StatusCode;
+//! use oauth2::prelude::*;
+//! use oauth2::{
+//!     AuthUrl,
+//!     ClientId,
+//!     ClientSecret,
+//!     HttpRequest,
+//!     HttpResponse,
+//!     TokenUrl
+//! };
+//! use oauth2::basic::BasicClient;
+//! use url::Url;
+//!
+//! # fn err_wrapper() -> Result<(), Box<std::error::Error>> {
+//! let client =
+//!     BasicClient::new(
+//!         ClientId::new("client_id".to_string()),
+//!         Some(ClientSecret::new("client_secret".to_string())),
+//!         AuthUrl::new(Url::parse("http://authorize")?),
+//!         Some(TokenUrl::new(Url::parse("http://token")?))
+//!     )
+//!         .set_http_client(|_request: HttpRequest| {
+//!             // Send the request using your HTTP stack of choice.
+//!             let mut headers = HeaderMap::new();
+//!             headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+//!             Ok(HttpResponse {
+//!                 status_code: StatusCode::OK,
+//!                 headers,
+//!                 body: b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}".to_vec(),
+//!             })
+//!         });
+//!
+//! let token_result = client.exchange_client_credentials();
+//! # Ok(())
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! # Async/await
+//!
+//! Each of the `exchange_*` methods has an `exchange_*_async` counterpart that returns a `Future`
+//! instead of blocking the current thread. These methods send requests using the
+//! `AsyncHttpClient` configured via `Client::set_async_http_client`, and they validate responses
+//! exactly as the synchronous methods do, returning the same `TokenResponse` and
+//! `RequestTokenError` types.
+//!
+//! ## Example
+//!
+//! ```
+//! extern crate oauth2;
+//! extern crate url;
+//!
+//! use oauth2::prelude::*;
+//! use oauth2::{
+//!     AuthUrl,
+//!     ClientId,
+//!     ClientSecret,
+//!     HttpClientError,
+//!     HttpRequest,
+//!     HttpResponse,
+//!     TokenUrl
+//! };
+//! use oauth2::basic::BasicClient;
+//! use url::Url;
+//!
+//! async fn my_async_http_client(request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+//!     // Send the request using your async HTTP stack of choice.
+//! #   unimplemented!()
+//! }
+//!
+//! # async fn err_wrapper() -> Result<(), Box<std::error::Error>> {
+//! let client =
+//!     BasicClient::new(
+//!         ClientId::new("client_id".to_string()),
+//!         Some(ClientSecret::new("client_secret".to_string())),
+//!         AuthUrl::new(Url::parse("http://authorize")?),
+//!         Some(TokenUrl::new(Url::parse("http://token")?))
+//!     )
+//!         .set_async_http_client(my_async_http_client);
+//!
+//! let token_result = client.exchange_client_credentials_async().await;
+//! # Ok(())
+//! # }
+//! # fn main() {}
+//! ```
+//!
 //! # Other examples
 //!
 //! More specific implementations are available as part of the examples:
@@ -211,11 +383,21 @@
 //!
 
 extern crate base64;
-extern crate curl;
+#[cfg(feature = "jwt")]
+extern crate ed25519_dalek;
 extern crate failure;
 #[macro_use]
 extern crate failure_derive;
+extern crate futures_timer;
+extern crate hmac;
+extern crate http;
+extern crate httpdate;
+#[cfg(feature = "jwt")]
+extern crate p256;
+extern crate pem_rfc7468;
 extern crate rand;
+#[cfg(feature = "jwt")]
+extern crate rsa;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
@@ -223,24 +405,83 @@ extern crate serde_json;
 extern crate sha2;
 extern crate url;
 
+use std::collections::HashMap;
 use std::convert::Into;
 use std::fmt::Error as FormatterError;
 use std::fmt::{Debug, Display, Formatter};
-use std::io::Read;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::Deref;
-use std::time::Duration;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use curl::easy::Easy;
+use futures_timer::Delay;
+use http::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use http::method::Method;
+use http::status::StatusCode;
 use rand::{thread_rng, Rng};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
-use url::Url;
+use url::{form_urlencoded, Url};
 
+use auth::{
+    ClientAuthentication, ClientAuthenticationContext, ClientSecretBasic, ClientSecretPost,
+    Endpoint, MtlsEndpointAliases,
+};
+use basic::{CibaErrorResponseType, DeviceCodeErrorResponseType, RevocationErrorResponseType};
+use dpop::{DpopKey, DPOP};
+use jwt::JwsSigningKey;
 use prelude::*;
 
+///
+/// HTTP client backed by the [curl](https://crates.io/crates/curl) crate. Requires the `curl`
+/// feature.
+///
+#[cfg(feature = "curl")]
+pub mod curl;
+
+///
+/// HTTP clients backed by the [reqwest](https://crates.io/crates/reqwest) crate. Requires the
+/// `reqwest` feature.
+///
+#[cfg(feature = "reqwest")]
+pub mod reqwest;
+
+///
+/// Client authentication methods used when communicating with the authorization server.
+///
+pub mod auth;
+
+///
+/// HTTP clients for recording token endpoint exchanges to a JSON cassette file and replaying
+/// them, e.g., for testing integrations with a particular authorization server offline.
+///
+pub mod cassette;
+
+///
+/// [DPoP](https://tools.ietf.org/html/rfc9449) proofs for binding access tokens to a key held by
+/// the client.
+///
+pub mod dpop;
+
+///
+/// Signing of JSON Web Tokens (JWTs), such as the assertions used by the JWT Bearer grant and
+/// for client authentication. The built-in RSA, EC and EdDSA signing keys require the `jwt`
+/// feature; other keys may be used by implementing `JwsSigningKey`.
+///
+pub mod jwt;
+
+///
+/// [User-Managed Access (UMA) 2.0](https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html)
+/// grant, which exchanges permission tickets for requesting party tokens (RPTs).
+///
+pub mod uma;
+
 const CONTENT_TYPE_JSON: &str = "application/json";
+const CONTENT_TYPE_FORMENCODED: &str = "application/x-www-form-urlencoded";
 
 ///
 /// Indicates whether requests to the authorization server should use basic authentication or
@@ -249,6 +490,10 @@ const CONTENT_TYPE_JSON: &str = "application/json";
 /// The default AuthType is *BasicAuth*, following the recommendation of
 /// [Section 2.3.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3.1).
 ///
+/// These correspond to the `ClientSecretBasic` and `ClientSecretPost` client authentication
+/// methods in the `auth` module. Other methods may be configured using
+/// `Client::set_client_authentication`.
+///
 #[derive(Clone, Debug)]
 pub enum AuthType {
     /// The client_id and client_secret will be included as part of the request body.
@@ -383,9 +628,9 @@ macro_rules! new_type {
                 &self.0
             }
         }
-        impl Into<$type> for $name {
-            fn into(self) -> $type {
-                self.0
+        impl From<$name> for $type {
+            fn from(s: $name) -> $type {
+                s.0
             }
         }
     }
@@ -511,6 +756,13 @@ new_type![///
 #[derive(Deserialize, Serialize)]
 ResponseType(String)];
 new_type![///
+/// Grant type sent via the `grant_type` parameter of a token request, such as an
+/// [extension grant](https://tools.ietf.org/html/rfc6749#section-4.5) identified by an absolute
+/// URI (e.g., `urn:ietf:params:oauth:grant-type:jwt-bearer`).
+///
+#[derive(Deserialize, Serialize)]
+GrantType(String)];
+new_type![///
 /// Resource owner's username used directly as an authorization grant to obtain an access
 /// token.
 ///
@@ -657,825 +909,4072 @@ new_secret_type![///
 /// token.
 ///
 ResourceOwnerPassword(String)];
-
+new_secret_type![///
+/// Password used for authenticating to an HTTP(S) proxy.
 ///
-/// Stores the configuration for an OAuth2 client.
+ProxyPassword(String)];
+new_type![///
+/// PEM-encoded X.509 certificate, or a bundle of concatenated PEM-encoded certificates.
 ///
-#[derive(Clone, Debug)]
-pub struct Client<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField, TE: ErrorResponseType> {
-    client_id: ClientId,
-    client_secret: Option<ClientSecret>,
-    auth_url: AuthUrl,
-    auth_type: AuthType,
-    token_url: Option<TokenUrl>,
-    scopes: Vec<Scope>,
-    redirect_url: Option<RedirectUrl>,
-    phantom_ef: PhantomData<EF>,
-    phantom_tt: PhantomData<TT>,
-    phantom_sf: PhantomData<SF>,
-    phantom_te: PhantomData<TE>,
-}
-
-impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField, TE: ErrorResponseType>
-    Client<EF, TT, SF, TE>
-{
-    ///
-    /// Initializes an OAuth2 client with the fields common to most OAuth2 flows.
-    ///
-    /// # Arguments
+CertificatePem(Vec<u8>)];
+new_secret_type![///
+/// PEM-encoded private key (e.g., PKCS#8) corresponding to a client certificate.
+///
+PrivateKeyPem(Vec<u8>)];
+new_type![
     ///
-    /// * `client_id` -  Client ID
-    /// * `client_secret` -  Optional client secret. A client secret is generally used for private
-    ///   (server-side) OAuth2 clients and omitted from public (client-side or native app) OAuth2
-    ///   clients (see [RFC 8252](https://tools.ietf.org/html/rfc8252)).
-    /// * `auth_url` -  Authorization endpoint: used by the client to obtain authorization from
-    ///   the resource owner via user-agent redirection. This URL is used in all standard OAuth2
-    ///   flows except the [Resource Owner Password Credentials
-    ///   Grant](https://tools.ietf.org/html/rfc6749#section-4.3) and the
-    ///   [Client Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.4).
-    /// * `token_url` - Token endpoint: used by the client to exchange an authorization grant
-    ///   (code) for an access token, typically with client authentication. This URL is used in
-    ///   all standard OAuth2 flows except the
-    ///   [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2). If this value is set
-    ///   to `None`, the `exchange_*` methods will return `Err(RequestTokenError::Other(_))`.
+    /// Base64-encoded SHA-256 digest of a DER-encoded X.509 SubjectPublicKeyInfo (SPKI), used
+    /// for public key pinning (see [RFC 7469](https://tools.ietf.org/html/rfc7469#section-2.4)).
     ///
-    pub fn new(
-        client_id: ClientId,
-        client_secret: Option<ClientSecret>,
-        auth_url: AuthUrl,
-        token_url: Option<TokenUrl>,
-    ) -> Self {
-        Client {
-            client_id,
-            client_secret,
-            auth_url,
-            auth_type: AuthType::BasicAuth,
-            token_url,
-            scopes: Vec::new(),
-            redirect_url: None,
-            phantom_ef: PhantomData,
-            phantom_tt: PhantomData,
-            phantom_sf: PhantomData,
-            phantom_te: PhantomData,
+    SpkiSha256(String)
+    impl {
+        ///
+        /// Computes the pin for the given DER-encoded SubjectPublicKeyInfo.
+        ///
+        pub fn from_spki_der(spki_der: &[u8]) -> Self {
+            SpkiSha256::new(base64::encode(&Sha256::digest(spki_der)))
         }
     }
+];
 
+new_type![
+    #[derive(Deserialize, Serialize)]
     ///
-    /// Appends a new scope to the authorization URL.
+    /// Base64url-encoded SHA-256 thumbprint of a DER-encoded X.509 certificate (`x5t#S256`), used
+    /// for binding access tokens to a client certificate (see
+    /// [Section 3.1 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-3.1)).
     ///
-    pub fn add_scope(mut self, scope: Scope) -> Self {
-        self.scopes.push(scope);
+    CertificateThumbprint(String)
+    impl {
+        ///
+        /// Computes the thumbprint of the given DER-encoded X.509 certificate.
+        ///
+        pub fn from_certificate_der(certificate_der: &[u8]) -> Self {
+            CertificateThumbprint::new(base64::encode_config(
+                &Sha256::digest(certificate_der),
+                base64::URL_SAFE_NO_PAD,
+            ))
+        }
 
-        self
+        ///
+        /// Computes the thumbprint of the given PEM-encoded X.509 certificate, which must
+        /// contain a single certificate.
+        ///
+        pub fn from_certificate_pem(certificate: &CertificatePem) -> Result<Self, String> {
+            let (label, certificate_der) = pem_rfc7468::decode_vec(certificate)
+                .map_err(|err| format!("Invalid PEM-encoded certificate: {}", err))?;
+            if label != "CERTIFICATE" {
+                return Err(format!("Expected a PEM-encoded certificate, but found {}", label));
+            }
+            Ok(Self::from_certificate_der(&certificate_der))
+        }
     }
+];
 
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// URL of the authorization server's device authorization endpoint (see
+/// [RFC 8628](https://tools.ietf.org/html/rfc8628#section-3.1)).
+///
+DeviceAuthorizationUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// URL on the authorization server at which the end user enters the user code.
+///
+EndUserVerificationUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_secret_type![///
+/// Device verification code returned by the device authorization endpoint and used by the client
+/// to poll the token endpoint.
+///
+#[derive(Deserialize, Serialize)]
+DeviceCode(String)];
+new_secret_type![///
+/// End-user verification code returned by the device authorization endpoint, which the user
+/// enters at the verification URL.
+///
+#[derive(Deserialize, Serialize)]
+UserCode(String)];
+new_secret_type![///
+/// Verification URL that includes the user code, allowing the user to skip entering it (e.g.,
+/// when displayed as a QR code).
+///
+#[derive(Deserialize, Serialize)]
+VerificationUriComplete(String)];
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// URL of the authorization server's token introspection endpoint (see
+/// [RFC 7662](https://tools.ietf.org/html/rfc7662#section-2)).
+///
+IntrospectionUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// URL of the authorization server's token revocation endpoint (see
+/// [RFC 7009](https://tools.ietf.org/html/rfc7009#section-2)).
+///
+RevocationUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_type![
     ///
-    /// Configures the type of client authentication used for communicating with the authorization
-    /// server.
-    ///
-    /// The default is to use HTTP Basic authentication, as recommended in
-    /// [Section 2.3.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3.1).
+    /// Hint about the type of the token submitted for introspection or revocation (see
+    /// [Section 2.1 of RFC 7662](https://tools.ietf.org/html/rfc7662#section-2.1) and
+    /// [Section 2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.1)).
     ///
-    pub fn set_auth_type(mut self, auth_type: AuthType) -> Self {
-        self.auth_type = auth_type;
-
-        self
+    #[derive(Deserialize, Serialize)]
+    TokenTypeHint(String)
+    impl {
+        ///
+        /// Indicates an access token (`access_token`).
+        ///
+        pub fn access_token() -> Self {
+            TokenTypeHint::new("access_token".to_string())
+        }
+
+        ///
+        /// Indicates a refresh token (`refresh_token`).
+        ///
+        pub fn refresh_token() -> Self {
+            TokenTypeHint::new("refresh_token".to_string())
+        }
+    }
+];
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// URL of the authorization server's backchannel authentication endpoint (see
+/// [OpenID Connect CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#auth_backchannel_endpoint)).
+///
+BackchannelAuthenticationUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_secret_type![///
+/// Identifier of a backchannel authentication request (`auth_req_id`), returned by the
+/// backchannel authentication endpoint and used by the client to poll the token endpoint.
+///
+#[derive(Deserialize, Serialize)]
+AuthenticationRequestId(String)];
+new_type![///
+/// Hint identifying the end user for whom authentication is being requested (e.g., an email
+/// address or phone number).
+///
+#[derive(Deserialize, Serialize)]
+LoginHint(String)];
+new_type![///
+/// Human-readable message displayed on both the consumption device and the authentication
+/// device, allowing the end user to verify that the two are interlinked.
+///
+#[derive(Deserialize, Serialize)]
+BindingMessage(String)];
+new_type![///
+/// Permission ticket issued by a UMA resource server or authorization server, representing the
+/// permissions requested by a client.
+///
+#[derive(Deserialize, Serialize)]
+PermissionTicket(String)];
+new_secret_type![///
+/// Token containing claims about the requesting party, pushed by the client to the UMA
+/// authorization server.
+///
+ClaimToken(String)];
+new_type![
+    ///
+    /// Format of a UMA claim token.
+    ///
+    #[derive(Deserialize, Serialize)]
+    ClaimTokenFormat(String)
+    impl {
+        ///
+        /// Indicates an OpenID Connect ID token
+        /// (`http://openid.net/specs/openid-connect-core-1_0.html#IDToken`).
+        ///
+        pub fn id_token() -> Self {
+            ClaimTokenFormat::new(
+                "http://openid.net/specs/openid-connect-core-1_0.html#IDToken".to_string(),
+            )
+        }
+    }
+];
+new_secret_type![///
+/// Persisted claims token (PCT) issued by a UMA authorization server, representing claims
+/// gathered from the requesting party that may be reused in later requests.
+///
+#[derive(Deserialize, Serialize)]
+PersistedClaimsToken(String)];
+new_secret_type![///
+/// SAML 2.0 assertion (XML) used as an authorization grant or for client authentication (see
+/// [RFC 7522](https://tools.ietf.org/html/rfc7522)).
+///
+SamlAssertion(String)];
+new_type![
+    ///
+    /// Identifier of a token type used by the
+    /// [Token Exchange](https://tools.ietf.org/html/rfc8693#section-3) grant, such as
+    /// `urn:ietf:params:oauth:token-type:access_token`.
+    ///
+    #[derive(Deserialize, Serialize)]
+    TokenTypeIdentifier(String)
+    impl {
+        ///
+        /// Indicates an OAuth 2.0 access token
+        /// (`urn:ietf:params:oauth:token-type:access_token`).
+        ///
+        pub fn access_token() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:access_token".to_string())
+        }
+        ///
+        /// Indicates an OAuth 2.0 refresh token
+        /// (`urn:ietf:params:oauth:token-type:refresh_token`).
+        ///
+        pub fn refresh_token() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:refresh_token".to_string())
+        }
+        ///
+        /// Indicates an OpenID Connect ID token (`urn:ietf:params:oauth:token-type:id_token`).
+        ///
+        pub fn id_token() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:id_token".to_string())
+        }
+        ///
+        /// Indicates a base64url-encoded SAML 1.1 assertion
+        /// (`urn:ietf:params:oauth:token-type:saml1`).
+        ///
+        pub fn saml1() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:saml1".to_string())
+        }
+        ///
+        /// Indicates a base64url-encoded SAML 2.0 assertion
+        /// (`urn:ietf:params:oauth:token-type:saml2`).
+        ///
+        pub fn saml2() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:saml2".to_string())
+        }
+        ///
+        /// Indicates a JSON Web Token (`urn:ietf:params:oauth:token-type:jwt`).
+        ///
+        pub fn jwt() -> Self {
+            TokenTypeIdentifier::new("urn:ietf:params:oauth:token-type:jwt".to_string())
+        }
     }
+];
+new_secret_type![///
+/// Security token representing the identity of the party on behalf of whom a
+/// [Token Exchange](https://tools.ietf.org/html/rfc8693) request is made.
+///
+SubjectToken(String)];
+new_secret_type![///
+/// Security token representing the identity of the acting party in a
+/// [Token Exchange](https://tools.ietf.org/html/rfc8693) request.
+///
+ActorToken(String)];
+new_type![///
+/// Logical name of the target service at which the client intends to use the requested token.
+///
+#[derive(Deserialize, Serialize)]
+Audience(String)];
+new_type![#[derive(Deserialize, Serialize)]
+///
+/// Absolute URI of the target service or resource at which the client intends to use the
+/// requested token (a resource indicator).
+///
+/// See [RFC 8707](https://tools.ietf.org/html/rfc8707).
+///
+ResourceUrl(
+    #[serde(
+        deserialize_with = "helpers::deserialize_url",
+        serialize_with = "helpers::serialize_url"
+    )]
+    Url
+)];
+new_type![///
+/// Type of an authorization details object (see
+/// [RFC 9396](https://tools.ietf.org/html/rfc9396#section-2)), which determines the meaning of
+/// its other fields (e.g., `payment_initiation`).
+///
+#[derive(Deserialize, Serialize)]
+AuthorizationDetailType(String)];
+
+///
+/// Stores the configuration for an OAuth2 client.
+///
+#[derive(Clone, Debug)]
+pub struct Client<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField, TE: ErrorResponseType> {
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    auth_url: AuthUrl,
+    client_authentication: Arc<dyn ClientAuthentication>,
+    endpoint_client_authentication: HashMap<Endpoint, Arc<dyn ClientAuthentication>>,
+    token_url: Option<TokenUrl>,
+    device_authorization_url: Option<DeviceAuthorizationUrl>,
+    backchannel_authentication_url: Option<BackchannelAuthenticationUrl>,
+    introspection_url: Option<IntrospectionUrl>,
+    revocation_url: Option<RevocationUrl>,
+    mtls_endpoint_aliases: Option<MtlsEndpointAliases>,
+    dpop_key: Option<DpopKey>,
+    scopes: Vec<Scope>,
+    resources: Vec<ResourceUrl>,
+    authorization_details: Vec<AuthorizationDetail>,
+    redirect_url: Option<RedirectUrl>,
+    http_client: Option<Arc<dyn HttpClient>>,
+    async_http_client: Option<Arc<dyn AsyncHttpClient>>,
+    http_config: HttpClientConfig,
+    retry_policy: Option<RetryPolicy>,
+    http_observers: Vec<Arc<dyn HttpObserver>>,
+    phantom_ef: PhantomData<EF>,
+    phantom_tt: PhantomData<TT>,
+    phantom_sf: PhantomData<SF>,
+    phantom_te: PhantomData<TE>,
+}
 
+impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField, TE: ErrorResponseType>
+    Client<EF, TT, SF, TE>
+{
     ///
-    /// Sets the the redirect URL used by the authorization endpoint.
+    /// Initializes an OAuth2 client with the fields common to most OAuth2 flows.
     ///
-    pub fn set_redirect_url(mut self, redirect_url: RedirectUrl) -> Self {
-        self.redirect_url = Some(redirect_url);
+    /// # Arguments
+    ///
+    /// * `client_id` -  Client ID
+    /// * `client_secret` -  Optional client secret. A client secret is generally used for private
+    ///   (server-side) OAuth2 clients and omitted from public (client-side or native app) OAuth2
+    ///   clients (see [RFC 8252](https://tools.ietf.org/html/rfc8252)).
+    /// * `auth_url` -  Authorization endpoint: used by the client to obtain authorization from
+    ///   the resource owner via user-agent redirection. This URL is used in all standard OAuth2
+    ///   flows except the [Resource Owner Password Credentials
+    ///   Grant](https://tools.ietf.org/html/rfc6749#section-4.3) and the
+    ///   [Client Credentials Grant](https://tools.ietf.org/html/rfc6749#section-4.4).
+    /// * `token_url` - Token endpoint: used by the client to exchange an authorization grant
+    ///   (code) for an access token, typically with client authentication. This URL is used in
+    ///   all standard OAuth2 flows except the
+    ///   [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2). If this value is set
+    ///   to `None`, the `exchange_*` methods will return `Err(RequestTokenError::Other(_))`.
+    ///
+    /// Token requests are sent using the `curl`-based HTTP client when the `curl` feature is
+    /// enabled (the default). Otherwise, an HTTP client must be configured using
+    /// `set_http_client` before calling any of the `exchange_*` methods.
+    ///
+    pub fn new(
+        client_id: ClientId,
+        client_secret: Option<ClientSecret>,
+        auth_url: AuthUrl,
+        token_url: Option<TokenUrl>,
+    ) -> Self {
+        Client {
+            client_id,
+            client_secret,
+            auth_url,
+            client_authentication: Arc::new(ClientSecretBasic),
+            endpoint_client_authentication: HashMap::new(),
+            token_url,
+            device_authorization_url: None,
+            backchannel_authentication_url: None,
+            introspection_url: None,
+            revocation_url: None,
+            mtls_endpoint_aliases: None,
+            dpop_key: None,
+            scopes: Vec::new(),
+            resources: Vec::new(),
+            authorization_details: Vec::new(),
+            redirect_url: None,
+            http_client: default_http_client(),
+            async_http_client: None,
+            http_config: HttpClientConfig::default(),
+            retry_policy: None,
+            http_observers: Vec::new(),
+            phantom_ef: PhantomData,
+            phantom_tt: PhantomData,
+            phantom_sf: PhantomData,
+            phantom_te: PhantomData,
+        }
+    }
+
+    ///
+    /// Appends a new scope to the authorization URL.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
 
         self
     }
 
     ///
-    /// Produces the full authorization URL used by the
-    /// [Authorization Code Grant](https://tools.ietf.org/html/rfc6749#section-4.1) flow, which
-    /// is the most common OAuth2 flow.
+    /// Appends a [resource indicator](https://tools.ietf.org/html/rfc8707) identifying a target
+    /// service or resource at which the requested tokens will be used.
     ///
-    /// # Arguments
+    /// Resource indicators are sent via the repeated `resource` parameter in authorization URLs
+    /// and in every request to the token endpoint (and to the device authorization and
+    /// backchannel authentication endpoints), unless the request explicitly specifies its own
+    /// `resource` parameters (e.g., via `TokenExchangeRequest::add_resource` or the
+    /// `extra_params` of `authorize_url_extension` and `exchange_code_extension`).
     ///
-    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
-    ///   between the request and callback. The authorization server includes this value when
-    ///   redirecting the user-agent back to the client.
+    pub fn add_resource(mut self, resource: ResourceUrl) -> Self {
+        self.resources.push(resource);
+
+        self
+    }
+
     ///
-    /// # Security Warning
+    /// Returns a copy of this client that sends the given resource indicators instead of those
+    /// added via `add_resource`, which allows a single client to request tokens for several
+    /// resources.
     ///
-    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
-    /// that this value matches the `state` parameter passed by the authorization server to the
-    /// redirect URI. Doing so mitigates
-    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
-    ///  attacks. To disable CSRF protections (NOT recommended), use `insecure::authorize_url`
-    ///  instead.
+    /// The copy shares this client's HTTP clients (including any pooled connections) and
+    /// observers.
     ///
-    pub fn authorize_url<F>(&self, state_fn: F) -> (Url, CsrfToken)
-    where
-        F: FnOnce() -> CsrfToken,
-    {
-        let state = state_fn();
-        (
-            self.authorize_url_impl::<&str>("code", Some(&state), None),
-            state,
-        )
+    pub fn with_resources(&self, resources: Vec<ResourceUrl>) -> Self {
+        Client {
+            resources,
+            ..self.clone()
+        }
     }
 
     ///
-    /// Produces the full authorization URL used by the
-    /// [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) flow.
+    /// Appends an authorization details object describing fine-grained permissions requested by
+    /// the client (see [RFC 9396](https://tools.ietf.org/html/rfc9396)).
     ///
-    /// # Arguments
+    /// Authorization details are sent as a JSON array via the `authorization_details` parameter
+    /// in authorization URLs and in every request to the token endpoint (and to the device
+    /// authorization and backchannel authentication endpoints), unless the request explicitly
+    /// specifies its own `authorization_details` parameter.
     ///
-    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
-    ///   between the request and callback. The authorization server includes this value when
-    ///   redirecting the user-agent back to the client.
+    pub fn add_authorization_detail(mut self, authorization_detail: AuthorizationDetail) -> Self {
+        self.authorization_details.push(authorization_detail);
+
+        self
+    }
+
     ///
-    /// # Security Warning
+    /// Returns a copy of this client that sends the given authorization details instead of
+    /// those added via `add_authorization_detail`.
     ///
-    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
-    /// that this value matches the `state` parameter passed by the authorization server to the
-    /// redirect URI. Doing so mitigates
-    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
-    ///  attacks. To disable CSRF protections (NOT recommended), use
-    /// `insecure::authorize_url_implicit` instead.
+    /// The copy shares this client's HTTP clients (including any pooled connections) and
+    /// observers.
     ///
-    pub fn authorize_url_implicit<F>(&self, state_fn: F) -> (Url, CsrfToken)
-    where
-        F: FnOnce() -> CsrfToken,
-    {
-        let state = state_fn();
-        (
-            self.authorize_url_impl::<&str>("token", Some(&state), None),
-            state,
-        )
+    pub fn with_authorization_details(
+        &self,
+        authorization_details: Vec<AuthorizationDetail>,
+    ) -> Self {
+        Client {
+            authorization_details,
+            ..self.clone()
+        }
     }
 
     ///
-    /// Produces the full authorization URL used by an OAuth2
-    /// [extension](https://tools.ietf.org/html/rfc6749#section-8.4).
+    /// Configures the type of client authentication used for communicating with the authorization
+    /// server.
     ///
-    /// # Arguments
+    /// The default is to use HTTP Basic authentication, as recommended in
+    /// [Section 2.3.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3.1).
     ///
-    /// * `response_type` - The response type this client expects from the authorization endpoint.
-    ///   For `"code"` or `"token"` response types, instead use the `authorize_url` or
-    ///   `authorize_url_implicit` functions, respectively.
-    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
-    ///   between the request and callback. The authorization server includes this value when
-    ///   redirecting the user-agent back to the client.
-    /// * `extra_params` - Additional parameters as required by the applicable OAuth2 extension(s).
-    ///   Callers should NOT specify any of the following parameters: `response_type`, `client_id`,
-    ///   `redirect_uri`, or `scope`.
+    /// This is equivalent to calling `set_client_authentication` with `auth::ClientSecretBasic`
+    /// or `auth::ClientSecretPost`.
     ///
-    /// # Security Warning
+    pub fn set_auth_type(self, auth_type: AuthType) -> Self {
+        match auth_type {
+            AuthType::RequestBody => self.set_client_authentication(ClientSecretPost),
+            AuthType::BasicAuth => self.set_client_authentication(ClientSecretBasic),
+        }
+    }
+
     ///
-    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
-    /// that this value matches the `state` parameter passed by the authorization server to the
-    /// redirect URI. Doing so mitigates
-    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
-    ///  attacks.
+    /// Sets the method used by the client to authenticate to the authorization server's
+    /// endpoints, except those configured via `set_endpoint_client_authentication`.
     ///
-    /// Callers should follow the security recommendations for any OAuth2 extensions used with
-    /// this function, which are beyond the scope of
-    /// [RFC 6749](https://tools.ietf.org/html/rfc6749).
-    pub fn authorize_url_extension<F, T>(
-        &self,
-        response_type: &ResponseType,
-        state_fn: F,
-        extra_params: &[(&str, T)],
-    ) -> (Url, CsrfToken)
+    /// The default is `auth::ClientSecretBasic` (HTTP Basic authentication).
+    ///
+    pub fn set_client_authentication<A>(mut self, client_authentication: A) -> Self
     where
-        F: FnOnce() -> CsrfToken,
-        T: AsRef<str> + Clone,
+        A: ClientAuthentication + 'static,
     {
-        let state = state_fn();
-        (
-            self.authorize_url_impl(response_type, Some(&state), Some(extra_params)),
-            state,
-        )
+        self.client_authentication = Arc::new(client_authentication);
+
+        self
     }
 
-    fn authorize_url_impl<T>(
-        &self,
-        response_type: &str,
-        state_opt: Option<&CsrfToken>,
-        extra_params_opt: Option<&[(&str, T)]>,
-    ) -> Url
+    ///
+    /// Sets the method used by the client to authenticate to a particular authorization server
+    /// endpoint, overriding the method configured via `set_client_authentication` (or
+    /// `set_auth_type`) for that endpoint.
+    ///
+    pub fn set_endpoint_client_authentication<A>(
+        mut self,
+        endpoint: Endpoint,
+        client_authentication: A,
+    ) -> Self
     where
-        T: AsRef<str> + Clone,
+        A: ClientAuthentication + 'static,
     {
-        let scopes = self
-            .scopes
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        let mut pairs: Vec<(&str, &str)> = vec![
-            ("response_type", response_type),
-            ("client_id", &self.client_id),
-        ];
+        self.endpoint_client_authentication
+            .insert(endpoint, Arc::new(client_authentication));
 
-        if let Some(ref redirect_url) = self.redirect_url {
-            pairs.push(("redirect_uri", redirect_url.as_str()));
-        }
+        self
+    }
 
-        if !scopes.is_empty() {
-            pairs.push(("scope", &scopes));
-        }
+    ///
+    /// Sets the the redirect URL used by the authorization endpoint.
+    ///
+    pub fn set_redirect_url(mut self, redirect_url: RedirectUrl) -> Self {
+        self.redirect_url = Some(redirect_url);
 
-        if let Some(state) = state_opt {
-            pairs.push(("state", state.secret()));
-        }
+        self
+    }
 
-        let mut url: Url = (*self.auth_url).clone();
+    ///
+    /// Sets the device authorization endpoint used by the
+    /// [Device Authorization Grant](https://tools.ietf.org/html/rfc8628).
+    ///
+    pub fn set_device_authorization_url(
+        mut self,
+        device_authorization_url: DeviceAuthorizationUrl,
+    ) -> Self {
+        self.device_authorization_url = Some(device_authorization_url);
 
-        url.query_pairs_mut()
-            .extend_pairs(pairs.iter().map(|&(k, v)| (k, &v[..])));
+        self
+    }
 
-        if let Some(extra_params) = extra_params_opt {
-            url.query_pairs_mut()
-                .extend_pairs(extra_params.iter().cloned());
-        }
+    ///
+    /// Sets the backchannel authentication endpoint used by the
+    /// [Client-Initiated Backchannel Authentication](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html)
+    /// (CIBA) flow.
+    ///
+    pub fn set_backchannel_authentication_url(
+        mut self,
+        backchannel_authentication_url: BackchannelAuthenticationUrl,
+    ) -> Self {
+        self.backchannel_authentication_url = Some(backchannel_authentication_url);
 
-        url
+        self
     }
 
     ///
-    /// Exchanges a code produced by a successful authorization process with an access token.
+    /// Sets the token introspection endpoint used by `introspect`.
     ///
-    /// Acquires ownership of the `code` because authorization codes may only be used to retrieve
-    /// an access token from the authorization server.
+    pub fn set_introspection_url(mut self, introspection_url: IntrospectionUrl) -> Self {
+        self.introspection_url = Some(introspection_url);
+
+        self
+    }
+
     ///
-    /// See https://tools.ietf.org/html/rfc6749#section-4.1.3
+    /// Sets the token revocation endpoint used by `revoke_token`.
     ///
-    pub fn exchange_code(
-        &self,
-        code: AuthorizationCode,
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
-        self.exchange_code_extension::<&str>(code, &[])
+    pub fn set_revocation_url(mut self, revocation_url: RevocationUrl) -> Self {
+        self.revocation_url = Some(revocation_url);
+
+        self
     }
 
     ///
-    /// Exchanges a code produced by a successful authorization process with an access token.
-    ///
-    /// Acquires ownership of the `code` because authorization codes may only be used to retrieve
-    /// an access token from the authorization server.
+    /// Sets the HTTP client used for sending requests to the authorization server.
     ///
-    /// See https://tools.ietf.org/html/rfc6749#section-4.1.3
+    /// Any type implementing `HttpClient` may be used, including functions and closures of the
+    /// form `Fn(HttpRequest) -> Result<HttpResponse, HttpClientError>`. This allows the use of
+    /// HTTP stacks other than curl, as well as unit testing without a live HTTP server.
     ///
-    pub fn exchange_code_extension<T>(
-        &self,
-        code: AuthorizationCode,
-        extra_params: &[(&str, T)],
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>
+    pub fn set_http_client<C>(mut self, http_client: C) -> Self
     where
-        T: AsRef<str> + Clone,
+        C: HttpClient + 'static,
     {
-        // Make Clippy happy since we're intentionally taking ownership.
-        let code_owned = code;
-        let mut params: Vec<(&str, &str)> = vec![
-            ("grant_type", "authorization_code"),
-            ("code", code_owned.secret()),
-        ];
-
-        params.extend_from_slice(
-            &extra_params
-                .iter()
-                .map(|&(k, ref v)| (k, v.as_ref()))
-                .collect::<Vec<(&str, &str)>>(),
-        );
+        self.http_client = Some(Arc::new(http_client));
 
-        self.request_token(params)
+        self
     }
 
     ///
-    /// Requests an access token for the *password* grant type.
+    /// Sets the asynchronous HTTP client used by the `exchange_*_async` methods.
     ///
-    /// See https://tools.ietf.org/html/rfc6749#section-4.3.2
+    /// Any type implementing `AsyncHttpClient` may be used, including functions and closures of
+    /// the form `Fn(HttpRequest) -> F`, where `F` is a `Future` resolving to
+    /// `Result<HttpResponse, HttpClientError>`. No asynchronous HTTP client is configured by
+    /// default.
     ///
-    pub fn exchange_password(
-        &self,
-        username: &ResourceOwnerUsername,
-        password: &ResourceOwnerPassword,
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
-        // Generate the space-delimited scopes String before initializing params so that it has
-        // a long enough lifetime.
-        let scopes_opt = if !self.scopes.is_empty() {
-            Some(
-                self.scopes
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" "),
-            )
-        } else {
-            None
-        };
+    pub fn set_async_http_client<C>(mut self, async_http_client: C) -> Self
+    where
+        C: AsyncHttpClient + 'static,
+    {
+        self.async_http_client = Some(Arc::new(async_http_client));
 
-        let mut params = vec![
-            ("grant_type", "password"),
-            ("username", username),
-            ("password", password.secret()),
-        ];
+        self
+    }
 
-        if let Some(ref scopes) = scopes_opt {
-            params.push(("scope", scopes));
-        }
+    ///
+    /// Sets the maximum time allowed for establishing a connection to the authorization server.
+    ///
+    /// By default, the HTTP client's default connect timeout (if any) is used.
+    ///
+    pub fn set_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.http_config.connect_timeout = Some(connect_timeout);
 
-        self.request_token(params)
+        self
     }
 
     ///
-    /// Requests an access token for the *client credentials* grant type.
+    /// Sets the maximum time allowed for each request to the authorization server, including
+    /// connecting, sending the request, and receiving the response.
     ///
-    /// See https://tools.ietf.org/html/rfc6749#section-4.4.2
+    /// Requests exceeding this limit fail with `RequestTokenError::Timeout`. By default, requests
+    /// have no overall timeout.
     ///
-    pub fn exchange_client_credentials(
-        &self,
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
-        // Generate the space-delimited scopes String before initializing params so that it has
-        // a long enough lifetime.
-        let scopes_opt = if !self.scopes.is_empty() {
-            Some(
-                self.scopes
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" "),
-            )
-        } else {
-            None
-        };
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.http_config.timeout = Some(timeout);
 
-        let mut params: Vec<(&str, &str)> = vec![("grant_type", "client_credentials")];
+        self
+    }
 
-        if let Some(ref scopes) = scopes_opt {
-            params.push(("scope", scopes));
-        }
-        self.request_token(params)
+    ///
+    /// Sets the HTTP(S) proxy used for sending requests to the authorization server.
+    ///
+    /// By default, the HTTP client's default proxy behavior applies (typically honoring the
+    /// `http_proxy` and `https_proxy` environment variables).
+    ///
+    pub fn set_proxy(mut self, proxy: HttpProxy) -> Self {
+        self.http_config.proxy = Some(proxy);
+
+        self
     }
 
     ///
-    /// Exchanges a refresh token for an access token
+    /// Appends a host (or domain suffix) for which requests bypass the HTTP(S) proxy.
     ///
-    /// See https://tools.ietf.org/html/rfc6749#section-6
+    /// This applies to the proxy configured via `set_proxy` as well as to proxies configured via
+    /// environment variables, in which case the `no_proxy` environment variable is disregarded.
     ///
-    pub fn exchange_refresh_token(
-        &self,
-        refresh_token: &RefreshToken,
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
-        let params: Vec<(&str, &str)> = vec![
-            ("grant_type", "refresh_token"),
-            ("refresh_token", refresh_token.secret()),
-        ];
+    pub fn add_no_proxy(mut self, host: String) -> Self {
+        self.http_config.no_proxy.push(host);
 
-        self.request_token(params)
+        self
     }
 
-    fn post_request_token<'a, 'b: 'a>(
-        &'b self,
-        token_url: &TokenUrl,
-        mut params: Vec<(&'b str, &'a str)>,
-    ) -> Result<RequestTokenResponse, curl::Error> {
-        let mut easy = Easy::new();
-
-        // FIXME: add support for auth extensions? e.g., client_secret_jwt and private_key_jwt
-        match self.auth_type {
-            AuthType::RequestBody => {
-                params.push(("client_id", &self.client_id));
-                if let Some(ref client_secret) = self.client_secret {
-                    params.push(("client_secret", client_secret.secret()));
-                }
-            }
-            AuthType::BasicAuth => {
-                // Section 2.3.1 of RFC 6749 requires separately url-encoding the id and secret
-                // before using them as HTTP Basic auth username and password. Note that this is
-                // not standard for ordinary Basic auth, so curl won't do it for us.
-                let encoded_id = easy.url_encode(&self.client_id.as_bytes());
-                easy.username(&encoded_id)?;
+    ///
+    /// Adds a trusted root certificate (or bundle of certificates) used for verifying the
+    /// authorization server's TLS certificate.
+    ///
+    /// This is useful for authorization servers whose certificates are issued by a private
+    /// certificate authority. These certificates are trusted in addition to the HTTP client's
+    /// built-in root certificates (e.g., the system's CA bundle) unless
+    /// `set_builtin_root_certificates(false)` is called.
+    ///
+    /// Since curl only accepts a single CA bundle, the curl HTTP client reads the built-in root
+    /// certificates from the PEM file named by the `SSL_CERT_FILE` (or `CURL_CA_BUNDLE`)
+    /// environment variable, or else from the system's CA bundle at one of the locations used by
+    /// common Linux distributions, BSDs and macOS. Where no such file exists (e.g., on Windows),
+    /// requests using the curl HTTP client fail with `HttpClientError::Other` unless one of
+    /// these environment variables is set or `set_builtin_root_certificates(false)` is called.
+    ///
+    pub fn add_root_certificate(mut self, certificate: CertificatePem) -> Self {
+        self.http_config.root_certificates.push(certificate);
 
-                if let Some(ref client_secret) = self.client_secret {
-                    let encoded_secret = easy.url_encode(client_secret.secret().as_bytes());
-                    easy.password(&encoded_secret)?;
-                }
-            }
-        }
+        self
+    }
 
-        if let Some(ref redirect_url) = self.redirect_url {
-            params.push(("redirect_uri", redirect_url.as_str()));
-        }
+    ///
+    /// Sets whether the HTTP client's built-in root certificates (e.g., the system's CA bundle)
+    /// are trusted for verifying the authorization server's TLS certificate. Defaults to `true`.
+    ///
+    /// When set to `false`, only the certificates added via `add_root_certificate` are trusted.
+    ///
+    pub fn set_builtin_root_certificates(mut self, enabled: bool) -> Self {
+        self.http_config.builtin_root_certificates = enabled;
 
-        let form = url::form_urlencoded::Serializer::new(String::new())
-            .extend_pairs(params)
-            .finish()
-            .into_bytes();
-        let mut form_slice = &form[..];
+        self
+    }
+
+    ///
+    /// Pins the authorization server's TLS public key.
+    ///
+    /// When one or more pins are configured, requests fail unless the server's certificate
+    /// contains a public key matching one of the pins. Adding several pins allows for key
+    /// rotation.
+    ///
+    pub fn add_pinned_public_key(mut self, pin: SpkiSha256) -> Self {
+        self.http_config.pinned_public_keys.push(pin);
 
-        easy.url(&token_url.to_string()[..])?;
+        self
+    }
 
-        // Section 5.1 of RFC 6749 (https://tools.ietf.org/html/rfc6749#section-5.1) only permits
-        // JSON responses for this request. Some providers such as GitHub have off-spec behavior
-        // and not only support different response formats, but have non-JSON defaults. Explicitly
-        // request JSON here.
-        let mut headers = curl::easy::List::new();
-        let accept_header = format!("Accept: {}", CONTENT_TYPE_JSON);
-        headers.append(&accept_header)?;
-        easy.http_headers(headers)?;
+    ///
+    /// Sets the client certificate and private key presented to the authorization server for
+    /// mutual TLS.
+    ///
+    pub fn set_client_certificate(
+        mut self,
+        certificate: CertificatePem,
+        private_key: PrivateKeyPem,
+    ) -> Self {
+        self.http_config.client_certificate = Some((certificate, private_key));
 
-        easy.post(true)?;
-        easy.post_field_size(form.len() as u64)?;
+        self
+    }
 
-        let mut data = Vec::new();
-        {
-            let mut transfer = easy.transfer();
+    ///
+    /// Sets the endpoint aliases used for requests over mutual TLS (see
+    /// [Section 5 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-5)).
+    ///
+    /// When set, requests to each endpoint with an alias are sent to the alias instead of the
+    /// URL configured on the client. This is typically used along with
+    /// `set_client_certificate` and the `auth::TlsClientAuth` or `auth::SelfSignedTlsClientAuth`
+    /// client authentication methods, or to obtain certificate-bound access tokens.
+    ///
+    pub fn set_mtls_endpoint_aliases(mut self, mtls_endpoint_aliases: MtlsEndpointAliases) -> Self {
+        self.mtls_endpoint_aliases = Some(mtls_endpoint_aliases);
 
-            transfer.read_function(|buf| Ok(form_slice.read(buf).unwrap_or(0)))?;
+        self
+    }
 
-            transfer.write_function(|new_data| {
-                data.extend_from_slice(new_data);
-                Ok(new_data.len())
-            })?;
+    ///
+    /// Sets the key used for requesting [DPoP](https://tools.ietf.org/html/rfc9449)-bound access
+    /// tokens.
+    ///
+    /// A DPoP proof is attached to each request sent to the token endpoint, including refresh
+    /// token requests, as described in
+    /// [Section 5 of RFC 9449](https://tools.ietf.org/html/rfc9449#section-5). Requests sent to
+    /// other endpoints (e.g., the device authorization and token introspection endpoints) do not
+    /// include a proof. If the server responds with a `use_dpop_nonce` error, the request is
+    /// retried once with a proof containing the nonce provided by the server.
+    ///
+    pub fn set_dpop_key(mut self, dpop_key: DpopKey) -> Self {
+        self.dpop_key = Some(dpop_key);
 
-            transfer.perform()?;
-        }
+        self
+    }
 
-        let http_status = easy.response_code()?;
-        let content_type = easy.content_type()?;
+    ///
+    /// Sets the policy for retrying token requests that fail due to transient errors.
+    ///
+    /// Retries are only attempted for requests that are safe to send more than once:
+    ///
+    ///  * *client credentials* and *refresh token* grant requests;
+    ///  * token endpoint polls during the Device Authorization Grant and CIBA flows;
+    ///  * token introspection and revocation requests.
+    ///
+    /// Other requests are never sent more than once. For example, authorization codes may only be
+    /// used once, and repeating a device authorization request would start a second flow.
+    ///
+    /// Token revocation requests are retried according to `RetryPolicy::new(3)` if no policy is
+    /// set.
+    ///
+    pub fn set_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
 
-        Ok(RequestTokenResponse {
-            http_status,
-            content_type: content_type.map(|s| s.to_string()),
-            response_body: data,
-        })
+        self
     }
 
-    fn request_token(
-        &self,
-        params: Vec<(&str, &str)>,
-    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
-        let token_url = self.token_url.as_ref().ok_or_else(||
-                // Arguably, it could be better to panic in this case. However, there may be
-                // situations where the library user gets the authorization server's configuration
-                // dynamically. In those cases, it would be preferable to return an `Err` rather
-                // than panic. An example situation where this might arise is OpenID Connect
-                // discovery.
-                RequestTokenError::Other("token_url must not be `None`".to_string()))?;
-        let token_response = self
-            .post_request_token(token_url, params)
-            .map_err(RequestTokenError::Request)?;
-        if token_response.http_status != 200 {
-            let reason = token_response.response_body.as_slice();
-            if reason.is_empty() {
-                return Err(RequestTokenError::Other(
-                    "Server returned empty error response".to_string(),
-                ));
-            } else {
-                let error = match serde_json::from_slice::<ErrorResponse<TE>>(reason) {
-                    Ok(error) => RequestTokenError::ServerResponse(error),
-                    Err(error) => RequestTokenError::Parse(error, reason.to_vec()),
-                };
-                return Err(error);
-            }
-        }
-
-        // Validate that the response Content-Type is JSON.
-        token_response
-            .content_type
-            .map_or(Ok(()), |content_type|
-                // Section 3.1.1.1 of RFC 7231 indicates that media types are case insensitive and
-                // may be followed by optional whitespace and/or a parameter (e.g., charset).
-                // See https://tools.ietf.org/html/rfc7231#section-3.1.1.1.
-                if !content_type.to_lowercase().starts_with(CONTENT_TYPE_JSON) {
-                    Err(
-                        RequestTokenError::Other(
-                            format!(
-                                "Unexpected response Content-Type: `{}`, should be `{}`",
-                                content_type,
-                                CONTENT_TYPE_JSON
-                            )
-                        )
-                    )
-                } else {
-                    Ok(())
-                }
-            )?;
+    ///
+    /// Adds an observer that is notified of each token request sent to the authorization server
+    /// and each response received, with any secrets redacted.
+    ///
+    pub fn add_http_observer<O>(mut self, observer: O) -> Self
+    where
+        O: HttpObserver + 'static,
+    {
+        self.http_observers.push(Arc::new(observer));
 
-        if token_response.response_body.is_empty() {
-            Err(RequestTokenError::Other(
-                "Server returned empty response body".to_string(),
-            ))
-        } else {
-            let response_body = token_response.response_body.as_slice();
-            serde_json::from_slice(response_body)
-                .map_err(|e| RequestTokenError::Parse(e, response_body.to_vec()))
-        }
+        self
     }
-}
 
-impl<EF: ExtraTokenFields, TT: TokenType, TE: ErrorResponseType>
-    Client<EF, TT, StringScopeField, TE>
+    ///
+    /// Produces the full authorization URL used by the
+    /// [Authorization Code Grant](https://tools.ietf.org/html/rfc6749#section-4.1) flow, which
+    /// is the most common OAuth2 flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
+    ///   between the request and callback. The authorization server includes this value when
+    ///   redirecting the user-agent back to the client.
+    ///
+    /// # Security Warning
+    ///
+    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
+    /// that this value matches the `state` parameter passed by the authorization server to the
+    /// redirect URI. Doing so mitigates
+    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
+    ///  attacks. To disable CSRF protections (NOT recommended), use `insecure::authorize_url`
+    ///  instead.
+    ///
+    pub fn authorize_url<F>(&self, state_fn: F) -> (Url, CsrfToken)
+    where
+        F: FnOnce() -> CsrfToken,
+    {
+        let state = state_fn();
+        (
+            self.authorize_url_impl::<&str>("code", Some(&state), None),
+            state,
+        )
+    }
+
+    ///
+    /// Produces the full authorization URL used by the
+    /// [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
+    ///   between the request and callback. The authorization server includes this value when
+    ///   redirecting the user-agent back to the client.
+    ///
+    /// # Security Warning
+    ///
+    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
+    /// that this value matches the `state` parameter passed by the authorization server to the
+    /// redirect URI. Doing so mitigates
+    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
+    ///  attacks. To disable CSRF protections (NOT recommended), use
+    /// `insecure::authorize_url_implicit` instead.
+    ///
+    pub fn authorize_url_implicit<F>(&self, state_fn: F) -> (Url, CsrfToken)
+    where
+        F: FnOnce() -> CsrfToken,
+    {
+        let state = state_fn();
+        (
+            self.authorize_url_impl::<&str>("token", Some(&state), None),
+            state,
+        )
+    }
+
+    ///
+    /// Produces the full authorization URL used by an OAuth2
+    /// [extension](https://tools.ietf.org/html/rfc6749#section-8.4).
+    ///
+    /// # Arguments
+    ///
+    /// * `response_type` - The response type this client expects from the authorization endpoint.
+    ///   For `"code"` or `"token"` response types, instead use the `authorize_url` or
+    ///   `authorize_url_implicit` functions, respectively.
+    /// * `state_fn` - A function that returns an opaque value used by the client to maintain state
+    ///   between the request and callback. The authorization server includes this value when
+    ///   redirecting the user-agent back to the client.
+    /// * `extra_params` - Additional parameters as required by the applicable OAuth2 extension(s).
+    ///   Callers should NOT specify any of the following parameters: `response_type`, `client_id`,
+    ///   `redirect_uri`, or `scope`.
+    ///
+    /// # Security Warning
+    ///
+    /// Callers should use a fresh, unpredictable `state` for each authorization request and verify
+    /// that this value matches the `state` parameter passed by the authorization server to the
+    /// redirect URI. Doing so mitigates
+    /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
+    ///  attacks.
+    ///
+    /// Callers should follow the security recommendations for any OAuth2 extensions used with
+    /// this function, which are beyond the scope of
+    /// [RFC 6749](https://tools.ietf.org/html/rfc6749).
+    pub fn authorize_url_extension<F, T>(
+        &self,
+        response_type: &ResponseType,
+        state_fn: F,
+        extra_params: &[(&str, T)],
+    ) -> (Url, CsrfToken)
+    where
+        F: FnOnce() -> CsrfToken,
+        T: AsRef<str> + Clone,
+    {
+        let state = state_fn();
+        (
+            self.authorize_url_impl(response_type, Some(&state), Some(extra_params)),
+            state,
+        )
+    }
+
+    fn authorize_url_impl<T>(
+        &self,
+        response_type: &str,
+        state_opt: Option<&CsrfToken>,
+        extra_params_opt: Option<&[(&str, T)]>,
+    ) -> Url
+    where
+        T: AsRef<str> + Clone,
+    {
+        let scopes = self
+            .scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let has_extra_param = |param_name: &str| {
+            extra_params_opt.is_some_and(|extra_params| {
+                extra_params.iter().any(|&(name, _)| name == param_name)
+            })
+        };
+        let authorization_details_opt = if has_extra_param("authorization_details") {
+            None
+        } else {
+            authorization_details_param(&self.authorization_details)
+        };
+
+        let mut pairs: Vec<(&str, &str)> = vec![
+            ("response_type", response_type),
+            ("client_id", &self.client_id),
+        ];
+
+        if let Some(ref redirect_url) = self.redirect_url {
+            pairs.push(("redirect_uri", redirect_url.as_str()));
+        }
+
+        if !scopes.is_empty() {
+            pairs.push(("scope", &scopes));
+        }
+
+        // Resource indicators and authorization details passed explicitly via `extra_params`
+        // take precedence over those configured on the client.
+        if !has_extra_param("resource") {
+            for resource in &self.resources {
+                pairs.push(("resource", resource.as_str()));
+            }
+        }
+
+        if let Some(ref authorization_details) = authorization_details_opt {
+            pairs.push(("authorization_details", authorization_details));
+        }
+
+        if let Some(state) = state_opt {
+            pairs.push(("state", state.secret()));
+        }
+
+        let mut url: Url = (*self.auth_url).clone();
+
+        url.query_pairs_mut()
+            .extend_pairs(pairs.iter().map(|&(k, v)| (k, &v[..])));
+
+        if let Some(extra_params) = extra_params_opt {
+            url.query_pairs_mut()
+                .extend_pairs(extra_params.iter().cloned());
+        }
+
+        url
+    }
+
+    ///
+    /// Exchanges a code produced by a successful authorization process with an access token.
+    ///
+    /// Acquires ownership of the `code` because authorization codes may only be used to retrieve
+    /// an access token from the authorization server.
+    ///
+    /// See https://tools.ietf.org/html/rfc6749#section-4.1.3
+    ///
+    pub fn exchange_code(
+        &self,
+        code: AuthorizationCode,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.exchange_code_extension::<&str>(code, &[])
+    }
+
+    ///
+    /// Exchanges a code produced by a successful authorization process with an access token.
+    ///
+    /// Acquires ownership of the `code` because authorization codes may only be used to retrieve
+    /// an access token from the authorization server.
+    ///
+    /// See https://tools.ietf.org/html/rfc6749#section-4.1.3
+    ///
+    pub fn exchange_code_extension<T>(
+        &self,
+        code: AuthorizationCode,
+        extra_params: &[(&str, T)],
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>
+    where
+        T: AsRef<str> + Clone,
+    {
+        self.send_request(&self.prepare_code_request(code, extra_params)?, false)
+    }
+
+    ///
+    /// Requests an access token for the *password* grant type.
+    ///
+    /// See https://tools.ietf.org/html/rfc6749#section-4.3.2
+    ///
+    pub fn exchange_password(
+        &self,
+        username: &ResourceOwnerUsername,
+        password: &ResourceOwnerPassword,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_password_request(username, password)?, false)
+    }
+
+    ///
+    /// Requests an access token for the *client credentials* grant type.
+    ///
+    /// See https://tools.ietf.org/html/rfc6749#section-4.4.2
+    ///
+    pub fn exchange_client_credentials(
+        &self,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_client_credentials_request()?, true)
+    }
+
+    ///
+    /// Exchanges a refresh token for an access token
+    ///
+    /// See https://tools.ietf.org/html/rfc6749#section-6
+    ///
+    pub fn exchange_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_refresh_token_request(refresh_token)?, true)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_code`, which sends the request using the HTTP client
+    /// configured via `set_async_http_client`.
+    ///
+    pub fn exchange_code_async(
+        &self,
+        code: AuthorizationCode,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.exchange_code_extension_async::<&str>(code, &[])
+    }
+
+    ///
+    /// Asynchronous version of `exchange_code_extension`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_code_extension_async<T>(
+        &self,
+        code: AuthorizationCode,
+        extra_params: &[(&str, T)],
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>>
+    where
+        T: AsRef<str> + Clone,
+    {
+        self.send_request_async(self.prepare_code_request(code, extra_params), false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_password`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_password_async(
+        &self,
+        username: &ResourceOwnerUsername,
+        password: &ResourceOwnerPassword,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_password_request(username, password), false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_client_credentials`, which sends the request using the
+    /// HTTP client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_client_credentials_async(
+        &self,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_client_credentials_request(), true)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_refresh_token`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_refresh_token_async(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_refresh_token_request(refresh_token), true)
+    }
+
+    ///
+    /// Exchanges a security token for another token using the
+    /// [Token Exchange](https://tools.ietf.org/html/rfc8693) grant (e.g., to obtain a token for a
+    /// downstream service on behalf of the user identified by an incoming access token).
+    ///
+    /// This request is never retried (see `set_retry_policy`), since the authorization server may
+    /// treat the subject or actor token as single-use.
+    ///
+    /// See https://tools.ietf.org/html/rfc8693#section-2.1
+    ///
+    pub fn exchange_token(
+        &self,
+        request: &TokenExchangeRequest,
+    ) -> Result<TokenExchangeResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_token_exchange_request(request)?, false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_token`, which sends the request using the HTTP client
+    /// configured via `set_async_http_client`.
+    ///
+    pub fn exchange_token_async(
+        &self,
+        request: &TokenExchangeRequest,
+    ) -> impl Future<Output = Result<TokenExchangeResponse<EF, TT, SF>, RequestTokenError<TE>>>
+    {
+        self.send_request_async(self.prepare_token_exchange_request(request), false)
+    }
+
+    ///
+    /// Requests an access token using a SAML 2.0 assertion as an authorization grant.
+    ///
+    /// The assertion is base64url-encoded before being sent to the token endpoint, along with
+    /// the client credentials as configured via `set_client_authentication`.
+    ///
+    /// See https://tools.ietf.org/html/rfc7522#section-2.1
+    ///
+    pub fn exchange_saml2_assertion(
+        &self,
+        assertion: &SamlAssertion,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_saml2_assertion_request(assertion)?, false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_saml2_assertion`, which sends the request using the
+    /// HTTP client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_saml2_assertion_async(
+        &self,
+        assertion: &SamlAssertion,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_saml2_assertion_request(assertion), false)
+    }
+
+    ///
+    /// Requests an access token using a signed JWT as an authorization grant (e.g., for service
+    /// accounts).
+    ///
+    /// A new assertion (with fresh `iat`, `exp` and `jti` claims) is signed for each call. The
+    /// assertion's audience defaults to the token endpoint URL.
+    ///
+    /// See https://tools.ietf.org/html/rfc7523#section-2.1
+    ///
+    pub fn exchange_jwt_bearer(
+        &self,
+        request: &JwtBearerRequest,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_jwt_bearer_request(request)?, false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_jwt_bearer`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_jwt_bearer_async(
+        &self,
+        request: &JwtBearerRequest,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_jwt_bearer_request(request), false)
+    }
+
+    ///
+    /// Requests an access token using an arbitrary grant type, such as a vendor-specific
+    /// [extension grant](https://tools.ietf.org/html/rfc6749#section-4.5).
+    ///
+    /// The `grant_type` and `params` are sent to the token endpoint along with the client
+    /// credentials (see `set_client_authentication`) and the redirect URL (if set). The client's
+    /// scopes are not included automatically; add a `scope` parameter to `params` if required.
+    ///
+    /// The response is parsed as `T` and error responses as `ErrorResponse<E>`, which allows
+    /// grants that return something other than a `TokenResponse` (or that define additional
+    /// error codes) to be used with the same `Client`. Since the semantics of the grant are
+    /// unknown, the request is never retried (see `set_retry_policy`).
+    ///
+    pub fn exchange_custom_grant<T, E, P>(
+        &self,
+        grant_type: &GrantType,
+        params: &[(&str, P)],
+    ) -> Result<T, RequestTokenError<E>>
+    where
+        T: DeserializeOwned,
+        E: ErrorResponseType,
+        P: AsRef<str>,
+    {
+        self.send_request(
+            &self.prepare_custom_grant_request(grant_type, params)?,
+            false,
+        )
+    }
+
+    ///
+    /// Asynchronous version of `exchange_custom_grant`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_custom_grant_async<T, E, P>(
+        &self,
+        grant_type: &GrantType,
+        params: &[(&str, P)],
+    ) -> impl Future<Output = Result<T, RequestTokenError<E>>>
+    where
+        T: DeserializeOwned,
+        E: ErrorResponseType,
+        P: AsRef<str>,
+    {
+        self.send_request_async(self.prepare_custom_grant_request(grant_type, params), false)
+    }
+
+    ///
+    /// Requests a device code and user code from the device authorization endpoint, which is the
+    /// first step of the [Device Authorization Grant](https://tools.ietf.org/html/rfc8628).
+    ///
+    /// The client authenticates to the device authorization endpoint in the same manner as to the
+    /// token endpoint (see `set_client_authentication`), unless configured otherwise via
+    /// `set_endpoint_client_authentication`. Public clients without a client secret typically
+    /// need to use `auth::NoAuthentication`, which sends only the `client_id` parameter.
+    ///
+    /// This request is never retried (see `set_retry_policy`), since each attempt would issue a new
+    /// device code and user code.
+    ///
+    /// If `set_device_authorization_url` has not been called, this method returns
+    /// `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://tools.ietf.org/html/rfc8628#section-3.1
+    ///
+    pub fn exchange_device_code(
+        &self,
+    ) -> Result<DeviceAuthorizationResponse, RequestTokenError<TE>> {
+        self.send_request(&self.prepare_device_authorization_request()?, false)
+    }
+
+    ///
+    /// Polls the token endpoint until the user completes the authorization started by
+    /// `exchange_device_code`, blocking the current thread in between polls.
+    ///
+    /// Polls are sent every `interval` seconds (as returned by the device authorization
+    /// endpoint), and the interval is increased by 5 seconds each time the server responds with
+    /// `slow_down`. Polling continues while the server responds with `authorization_pending` and
+    /// stops once an access token is issued or any other error is returned (e.g.,
+    /// `access_denied` or `expired_token`). If the device code expires before the user completes
+    /// authorization, this method returns `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://tools.ietf.org/html/rfc8628#section-3.4
+    ///
+    pub fn exchange_device_access_token(
+        &self,
+        details: &DeviceAuthorizationResponse,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<DeviceCodeErrorResponseType>> {
+        let request = self.prepare_device_access_token_request(details)?;
+        let mut polling = TokenPolling::new(
+            details.expires_in(),
+            details.interval(),
+            "Device code expired before authorization was completed",
+        );
+        loop {
+            thread::sleep(polling.next_delay()?);
+            if let Some(result) = polling.handle(self.send_request(&request, true)) {
+                return result;
+            }
+        }
+    }
+
+    ///
+    /// Asynchronous version of `exchange_device_code`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_device_code_async(
+        &self,
+    ) -> impl Future<Output = Result<DeviceAuthorizationResponse, RequestTokenError<TE>>> {
+        self.send_request_async(self.prepare_device_authorization_request(), false)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_device_access_token`, which sends requests using the
+    /// HTTP client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_device_access_token_async(
+        &self,
+        details: &DeviceAuthorizationResponse,
+    ) -> impl Future<
+        Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<DeviceCodeErrorResponseType>>,
+    > {
+        let request = self.prepare_device_access_token_request(details);
+        let mut polling = TokenPolling::new(
+            details.expires_in(),
+            details.interval(),
+            "Device code expired before authorization was completed",
+        );
+        let sender = self.async_sender(true);
+
+        async move {
+            let request = request?;
+            loop {
+                Delay::new(polling.next_delay()?).await;
+                if let Some(result) = polling.handle(sender.send(&request).await) {
+                    return result;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Starts a [Client-Initiated Backchannel Authentication](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html)
+    /// (CIBA) flow by asking the authorization server to authenticate the end user identified by
+    /// the request's login hint on their authentication device (e.g., their phone).
+    ///
+    /// The client authenticates to the backchannel authentication endpoint in the same manner as
+    /// to the token endpoint (see `set_client_authentication`), unless configured otherwise via
+    /// `set_endpoint_client_authentication`. This request is never retried (see
+    /// `set_retry_policy`) since doing so may notify the end user more than once.
+    ///
+    /// If `set_backchannel_authentication_url` has not been called, this method returns
+    /// `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#auth_request
+    ///
+    pub fn exchange_backchannel_authentication(
+        &self,
+        request: &BackchannelAuthenticationRequest,
+    ) -> Result<BackchannelAuthenticationResponse, RequestTokenError<TE>> {
+        self.send_request(
+            &self.prepare_backchannel_authentication_request(request)?,
+            false,
+        )
+    }
+
+    ///
+    /// Polls the token endpoint until the end user completes the authentication started by
+    /// `exchange_backchannel_authentication`, blocking the current thread in between polls.
+    ///
+    /// Polls are sent every `interval` seconds (as returned by the backchannel authentication
+    /// endpoint), and the interval is increased by 5 seconds each time the server responds with
+    /// `slow_down`. Polling continues while the server responds with `authorization_pending` and
+    /// stops once an access token is issued or any other error is returned (e.g.,
+    /// `access_denied` or `expired_token`). If the authentication request expires before the end
+    /// user completes authentication, this method returns `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.10.1
+    ///
+    pub fn exchange_backchannel_access_token(
+        &self,
+        details: &BackchannelAuthenticationResponse,
+    ) -> Result<TokenResponse<EF, TT, SF>, RequestTokenError<CibaErrorResponseType>> {
+        let request = self.prepare_backchannel_access_token_request(details)?;
+        let mut polling = TokenPolling::new(
+            details.expires_in(),
+            details.interval(),
+            "Authentication request expired before authorization was completed",
+        );
+        loop {
+            thread::sleep(polling.next_delay()?);
+            if let Some(result) = polling.handle(self.send_request(&request, true)) {
+                return result;
+            }
+        }
+    }
+
+    ///
+    /// Asynchronous version of `exchange_backchannel_authentication`, which sends the request
+    /// using the HTTP client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_backchannel_authentication_async(
+        &self,
+        request: &BackchannelAuthenticationRequest,
+    ) -> impl Future<Output = Result<BackchannelAuthenticationResponse, RequestTokenError<TE>>>
+    {
+        self.send_request_async(
+            self.prepare_backchannel_authentication_request(request),
+            false,
+        )
+    }
+
+    ///
+    /// Asynchronous version of `exchange_backchannel_access_token`, which sends requests using
+    /// the HTTP client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_backchannel_access_token_async(
+        &self,
+        details: &BackchannelAuthenticationResponse,
+    ) -> impl Future<Output = Result<TokenResponse<EF, TT, SF>, RequestTokenError<CibaErrorResponseType>>>
+    {
+        let request = self.prepare_backchannel_access_token_request(details);
+        let mut polling = TokenPolling::new(
+            details.expires_in(),
+            details.interval(),
+            "Authentication request expired before authorization was completed",
+        );
+        let sender = self.async_sender(true);
+
+        async move {
+            let request = request?;
+            loop {
+                Delay::new(polling.next_delay()?).await;
+                if let Some(result) = polling.handle(sender.send(&request).await) {
+                    return result;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Queries the token introspection endpoint for the state of the given access or refresh
+    /// token (e.g., whether it is active), as described in
+    /// [RFC 7662](https://tools.ietf.org/html/rfc7662).
+    ///
+    /// The client authenticates to the introspection endpoint in the same manner as to the token
+    /// endpoint (see `set_client_authentication`), unless configured otherwise via
+    /// `set_endpoint_client_authentication`. The response's extra fields are parsed as `IF`,
+    /// which may be `EmptyExtraTokenFields` if no extra fields are needed.
+    ///
+    /// If `set_introspection_url` has not been called, this method returns
+    /// `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://tools.ietf.org/html/rfc7662#section-2.1
+    ///
+    pub fn introspect<IF: ExtraTokenFields, T: RevocableToken>(
+        &self,
+        token: &T,
+        token_type_hint: Option<&TokenTypeHint>,
+    ) -> Result<IntrospectionResponse<IF>, RequestTokenError<TE>> {
+        self.send_request(
+            &self.prepare_introspection_request(token, token_type_hint)?,
+            true,
+        )
+    }
+
+    ///
+    /// Asynchronous version of `introspect`, which sends the request using the HTTP client
+    /// configured via `set_async_http_client`.
+    ///
+    pub fn introspect_async<IF: ExtraTokenFields, T: RevocableToken>(
+        &self,
+        token: &T,
+        token_type_hint: Option<&TokenTypeHint>,
+    ) -> impl Future<Output = Result<IntrospectionResponse<IF>, RequestTokenError<TE>>> {
+        self.send_request_async(
+            self.prepare_introspection_request(token, token_type_hint),
+            true,
+        )
+    }
+
+    ///
+    /// Revokes the given access or refresh token at the token revocation endpoint, as described
+    /// in [RFC 7009](https://tools.ietf.org/html/rfc7009), sending the corresponding
+    /// `token_type_hint`.
+    ///
+    /// The client authenticates to the revocation endpoint in the same manner as to the token
+    /// endpoint (see `set_client_authentication`), unless configured otherwise via
+    /// `set_endpoint_client_authentication`. Any `200 OK` response indicates success, including
+    /// when the token was already invalid.
+    ///
+    /// Since revocation is idempotent, the request is retried on transient failures, including
+    /// `503 Service Unavailable` responses with a `Retry-After` header as described in
+    /// [Section 2.2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.2.1). Retries
+    /// follow the policy configured via `set_retry_policy`, or `RetryPolicy::new(3)` if none is
+    /// set. If the server still responds with `503 Service Unavailable`, this method returns
+    /// `Err(RequestTokenError::ServiceUnavailable(_))` containing the server's `Retry-After`
+    /// delay, if any, after which the caller may try again.
+    ///
+    /// If `set_revocation_url` has not been called, this method returns
+    /// `Err(RequestTokenError::Other(_))`.
+    ///
+    /// See https://tools.ietf.org/html/rfc7009#section-2.1
+    ///
+    pub fn revoke_token<T: RevocableToken>(
+        &self,
+        token: &T,
+    ) -> Result<(), RequestTokenError<RevocationErrorResponseType>> {
+        parse_revocation_response(self.send_http_request_with_policy(
+            &self.prepare_revocation_request(token)?,
+            Some(&self.revocation_retry_policy()),
+        )?)
+    }
+
+    ///
+    /// Asynchronous version of `revoke_token`, which sends the request using the HTTP client
+    /// configured via `set_async_http_client`.
+    ///
+    pub fn revoke_token_async<T: RevocableToken>(
+        &self,
+        token: &T,
+    ) -> impl Future<Output = Result<(), RequestTokenError<RevocationErrorResponseType>>> {
+        let request = self.prepare_revocation_request(token);
+        let sender = self.async_sender_with_policy(Some(self.revocation_retry_policy()));
+
+        async move { parse_revocation_response(sender.send_http_request(&request?).await?) }
+    }
+
+    fn prepare_code_request<T>(
+        &self,
+        code: AuthorizationCode,
+        extra_params: &[(&str, T)],
+    ) -> Result<PreparedRequest, RequestTokenError<TE>>
+    where
+        T: AsRef<str> + Clone,
+    {
+        // Make Clippy happy since we're intentionally taking ownership.
+        let code_owned = code;
+        let mut params: Vec<(&str, &str)> = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code_owned.secret()),
+        ];
+
+        params.extend_from_slice(
+            &extra_params
+                .iter()
+                .map(|&(k, ref v)| (k, v.as_ref()))
+                .collect::<Vec<(&str, &str)>>(),
+        );
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_password_request(
+        &self,
+        username: &ResourceOwnerUsername,
+        password: &ResourceOwnerPassword,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = self.scopes_param();
+
+        let mut params = vec![
+            ("grant_type", "password"),
+            ("username", username),
+            ("password", password.secret()),
+        ];
+
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_client_credentials_request(&self) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = self.scopes_param();
+
+        let mut params: Vec<(&str, &str)> = vec![("grant_type", "client_credentials")];
+
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_refresh_token_request(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        let params: Vec<(&str, &str)> = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.secret()),
+        ];
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_token_exchange_request(
+        &self,
+        request: &TokenExchangeRequest,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = if request.scopes.is_empty() {
+            self.scopes_param()
+        } else {
+            Some(
+                request
+                    .scopes
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        };
+
+        let mut params: Vec<(&str, &str)> = vec![(
+            "grant_type",
+            "urn:ietf:params:oauth:grant-type:token-exchange",
+        )];
+        for resource in &request.resources {
+            params.push(("resource", resource.as_str()));
+        }
+        for audience in &request.audiences {
+            params.push(("audience", audience));
+        }
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+        if let Some(ref requested_token_type) = request.requested_token_type {
+            params.push(("requested_token_type", requested_token_type));
+        }
+        params.push(("subject_token", request.subject_token.secret()));
+        params.push(("subject_token_type", &request.subject_token_type));
+        if let Some((ref actor_token, ref actor_token_type)) = request.actor_token {
+            params.push(("actor_token", actor_token.secret()));
+            params.push(("actor_token_type", actor_token_type));
+        }
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_saml2_assertion_request(
+        &self,
+        assertion: &SamlAssertion,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        // See https://tools.ietf.org/html/rfc7522#section-2.1.
+        let encoded_assertion =
+            base64::encode_config(assertion.secret().as_bytes(), base64::URL_SAFE_NO_PAD);
+
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = self.scopes_param();
+
+        let mut params = vec![
+            (
+                "grant_type",
+                "urn:ietf:params:oauth:grant-type:saml2-bearer",
+            ),
+            ("assertion", encoded_assertion.as_str()),
+        ];
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_jwt_bearer_request(
+        &self,
+        request: &JwtBearerRequest,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        let audience = match request.audience {
+            Some(ref audience) => audience.clone(),
+            None => self
+                .token_url
+                .as_ref()
+                .ok_or_else(|| {
+                    RequestTokenError::Other("token_url must not be `None`".to_string())
+                })?
+                .to_string(),
+        };
+        let assertion = request
+            .assertion(&audience)
+            .map_err(|err| RequestTokenError::Other(err.to_string()))?;
+
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = self.scopes_param();
+
+        let mut params = vec![
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", assertion.as_str()),
+        ];
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_custom_grant_request<E, P>(
+        &self,
+        grant_type: &GrantType,
+        params: &[(&str, P)],
+    ) -> Result<PreparedRequest, RequestTokenError<E>>
+    where
+        E: ErrorResponseType,
+        P: AsRef<str>,
+    {
+        let mut all_params: Vec<(&str, &str)> = vec![("grant_type", grant_type)];
+        all_params.extend(params.iter().map(|(name, value)| (*name, value.as_ref())));
+
+        self.prepare_token_request(all_params)
+    }
+
+    fn prepare_device_authorization_request(
+        &self,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        let device_authorization_url = self.device_authorization_url.as_ref().ok_or_else(|| {
+            RequestTokenError::Other("device_authorization_url must not be `None`".to_string())
+        })?;
+
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = self.scopes_param();
+
+        let mut params = Vec::new();
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes.as_str()));
+        }
+
+        self.prepare_request(
+            Endpoint::DeviceAuthorization,
+            device_authorization_url,
+            params,
+            false,
+        )
+    }
+
+    fn prepare_device_access_token_request(
+        &self,
+        details: &DeviceAuthorizationResponse,
+    ) -> Result<PreparedRequest, RequestTokenError<DeviceCodeErrorResponseType>> {
+        let params: Vec<(&str, &str)> = vec![
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ("device_code", details.device_code().secret()),
+        ];
+
+        self.prepare_token_request(params)
+    }
+
+    fn prepare_backchannel_authentication_request(
+        &self,
+        request: &BackchannelAuthenticationRequest,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        let backchannel_authentication_url = self
+            .backchannel_authentication_url
+            .as_ref()
+            .ok_or_else(|| {
+                RequestTokenError::Other(
+                    "backchannel_authentication_url must not be `None`".to_string(),
+                )
+            })?;
+
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = if request.scopes.is_empty() {
+            self.scopes_param()
+        } else {
+            Some(
+                request
+                    .scopes
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        };
+
+        let mut params = Vec::new();
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes.as_str()));
+        }
+        params.push(("login_hint", &request.login_hint));
+        if let Some(ref binding_message) = request.binding_message {
+            params.push(("binding_message", binding_message));
+        }
+
+        self.prepare_request(
+            Endpoint::BackchannelAuthentication,
+            backchannel_authentication_url,
+            params,
+            false,
+        )
+    }
+
+    fn prepare_backchannel_access_token_request(
+        &self,
+        details: &BackchannelAuthenticationResponse,
+    ) -> Result<PreparedRequest, RequestTokenError<CibaErrorResponseType>> {
+        let params: Vec<(&str, &str)> = vec![
+            ("grant_type", "urn:openid:params:grant-type:ciba"),
+            ("auth_req_id", details.auth_req_id().secret()),
+        ];
+
+        self.prepare_token_request(params)
+    }
+
+    fn scopes_param(&self) -> Option<String> {
+        if !self.scopes.is_empty() {
+            Some(
+                self.scopes
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn prepare_introspection_request<T: RevocableToken>(
+        &self,
+        token: &T,
+        token_type_hint: Option<&TokenTypeHint>,
+    ) -> Result<PreparedRequest, RequestTokenError<TE>> {
+        let introspection_url = self.introspection_url.as_ref().ok_or_else(|| {
+            RequestTokenError::Other("introspection_url must not be `None`".to_string())
+        })?;
+
+        let mut params = vec![("token", token.token_secret())];
+        if let Some(token_type_hint) = token_type_hint {
+            params.push(("token_type_hint", token_type_hint.as_str()));
+        }
+
+        self.prepare_request(Endpoint::Introspection, introspection_url, params, false)
+    }
+
+    fn prepare_revocation_request<T: RevocableToken>(
+        &self,
+        token: &T,
+    ) -> Result<PreparedRequest, RequestTokenError<RevocationErrorResponseType>> {
+        let revocation_url = self.revocation_url.as_ref().ok_or_else(|| {
+            RequestTokenError::Other("revocation_url must not be `None`".to_string())
+        })?;
+
+        let token_type_hint = token.token_type_hint();
+        let mut params = vec![("token", token.token_secret())];
+        if let Some(ref token_type_hint) = token_type_hint {
+            params.push(("token_type_hint", token_type_hint.as_str()));
+        }
+
+        self.prepare_request(Endpoint::Revocation, revocation_url, params, false)
+    }
+
+    fn prepare_token_request<'a, 'b: 'a, E: ErrorResponseType>(
+        &'b self,
+        params: Vec<(&'b str, &'a str)>,
+    ) -> Result<PreparedRequest, RequestTokenError<E>> {
+        let token_url = self.token_url.as_ref().ok_or_else(||
+                // Arguably, it could be better to panic in this case. However, there may be
+                // situations where the library user gets the authorization server's configuration
+                // dynamically. In those cases, it would be preferable to return an `Err` rather
+                // than panic. An example situation where this might arise is OpenID Connect
+                // discovery.
+                RequestTokenError::Other("token_url must not be `None`".to_string()))?;
+
+        self.prepare_request(Endpoint::Token, token_url, params, true)
+    }
+
+    // Prepares a POST request with the given form parameters, which is authenticated each time it's
+    // sent (see `PreparedRequest`).
+    fn prepare_request<'a, 'b: 'a, E: ErrorResponseType>(
+        &'b self,
+        endpoint: Endpoint,
+        url: &Url,
+        params: Vec<(&'b str, &'a str)>,
+        include_redirect_url: bool,
+    ) -> Result<PreparedRequest, RequestTokenError<E>> {
+        // Resource indicators and authorization details only apply to requests for tokens, not
+        // to requests about existing tokens (e.g., introspection and revocation).
+        let requests_token = !matches!(endpoint, Endpoint::Introspection | Endpoint::Revocation);
+        let has_param = |param_name: &str| params.iter().any(|&(name, _)| name == param_name);
+        let has_resources = has_param("resource") || !requests_token;
+        let authorization_details_opt = if has_param("authorization_details") || !requests_token {
+            None
+        } else {
+            authorization_details_param(&self.authorization_details)
+        };
+        let mut params: Vec<(&str, &str)> = params;
+        let url = self
+            .mtls_endpoint_aliases
+            .as_ref()
+            .and_then(|aliases| aliases.url(endpoint))
+            .unwrap_or(url);
+
+        let mut headers = HeaderMap::new();
+
+        // Section 5.1 of RFC 6749 (https://tools.ietf.org/html/rfc6749#section-5.1) only permits
+        // JSON responses for this request. Some providers such as GitHub have off-spec behavior
+        // and not only support different response formats, but have non-JSON defaults. Explicitly
+        // request JSON here.
+        headers.append(ACCEPT, HeaderValue::from_static(CONTENT_TYPE_JSON));
+        headers.append(
+            CONTENT_TYPE,
+            HeaderValue::from_static(CONTENT_TYPE_FORMENCODED),
+        );
+
+        // Resource indicators and authorization details included explicitly in the request
+        // parameters (e.g., via `TokenExchangeRequest::add_resource`) take precedence over those
+        // configured on the client.
+        if !has_resources {
+            for resource in &self.resources {
+                params.push(("resource", resource.as_str()));
+            }
+        }
+        if let Some(ref authorization_details) = authorization_details_opt {
+            params.push(("authorization_details", authorization_details));
+        }
+
+        let client_authentication = self
+            .endpoint_client_authentication
+            .get(&endpoint)
+            .unwrap_or(&self.client_authentication);
+
+        Ok(PreparedRequest {
+            endpoint,
+            url: url.clone(),
+            headers,
+            params: params
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            redirect_url: self
+                .redirect_url
+                .as_ref()
+                .filter(|_| include_redirect_url)
+                .map(|redirect_url| redirect_url.to_string()),
+            client_authentication: client_authentication.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            token_url: self.token_url.clone(),
+            // DPoP proofs are only required for requests that issue access tokens.
+            dpop_key: self
+                .dpop_key
+                .clone()
+                .filter(|_| endpoint == Endpoint::Token),
+            config: self.http_config.clone(),
+        })
+    }
+
+    fn send_request<T, E>(
+        &self,
+        request: &PreparedRequest,
+        retryable: bool,
+    ) -> Result<T, RequestTokenError<E>>
+    where
+        T: DeserializeOwned,
+        E: ErrorResponseType,
+    {
+        parse_response(self.send_http_request(request, retryable)?)
+    }
+
+    // Sends the request and returns the final HTTP response without parsing it. Requests with
+    // `retryable` set to false (e.g., those containing an authorization code) are never sent
+    // more than once, regardless of the configured retry policy, unless the server requires a
+    // DPoP nonce (in which case it has rejected the request without processing it).
+    fn send_http_request<E: ErrorResponseType>(
+        &self,
+        request: &PreparedRequest,
+        retryable: bool,
+    ) -> Result<HttpResponse, RequestTokenError<E>> {
+        self.send_http_request_with_policy(
+            request,
+            self.retry_policy.as_ref().filter(|_| retryable),
+        )
+    }
+
+    fn send_http_request_with_policy<E: ErrorResponseType>(
+        &self,
+        request: &PreparedRequest,
+        retry_policy: Option<&RetryPolicy>,
+    ) -> Result<HttpResponse, RequestTokenError<E>> {
+        let http_response = self.send_http_request_with_retries(request, retry_policy)?;
+        if request.requires_dpop_nonce(&http_response) {
+            self.send_http_request_with_retries(request, retry_policy)
+        } else {
+            Ok(http_response)
+        }
+    }
+
+    fn send_http_request_with_retries<E: ErrorResponseType>(
+        &self,
+        request: &PreparedRequest,
+        retry_policy: Option<&RetryPolicy>,
+    ) -> Result<HttpResponse, RequestTokenError<E>> {
+        let http_client = self.http_client.as_ref().ok_or_else(|| {
+            RequestTokenError::Other(
+                "http_client must be set when the `curl` feature is disabled".to_string(),
+            )
+        })?;
+
+        let mut attempt = 1;
+        loop {
+            let http_request = request.build()?;
+            observe_request(&self.http_observers, &http_request);
+            let result = http_client.request(http_request);
+            observe_result(&self.http_observers, &result);
+            match retry_policy.and_then(|policy| policy.retry_delay(attempt, &result)) {
+                Some(delay) => {
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => return Ok(result?),
+            }
+        }
+    }
+
+    fn send_request_async<T, E>(
+        &self,
+        request: Result<PreparedRequest, RequestTokenError<E>>,
+        retryable: bool,
+    ) -> impl Future<Output = Result<T, RequestTokenError<E>>>
+    where
+        T: DeserializeOwned,
+        E: ErrorResponseType,
+    {
+        let sender = self.async_sender(retryable);
+
+        async move { sender.send(&request?).await }
+    }
+
+    fn async_sender(&self, retryable: bool) -> AsyncSender {
+        self.async_sender_with_policy(self.retry_policy.clone().filter(|_| retryable))
+    }
+
+    fn async_sender_with_policy(&self, retry_policy: Option<RetryPolicy>) -> AsyncSender {
+        AsyncSender {
+            http_client: self.async_http_client.clone(),
+            retry_policy,
+            http_observers: self.http_observers.clone(),
+        }
+    }
+
+    // Revocation requests are retried even if no retry policy is configured, since the
+    // revocation endpoint may ask the client to retry later (see
+    // https://tools.ietf.org/html/rfc7009#section-2.2.1).
+    fn revocation_retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+            .clone()
+            .unwrap_or_else(|| RetryPolicy::new(3))
+    }
+}
+
+// Request to the authorization server whose client authentication and DPoP proof (if any) are
+// generated anew each time it is sent, since both may contain JWTs that the server must not accept
+// more than once (see https://tools.ietf.org/html/rfc7523#section-3 and
+// https://tools.ietf.org/html/rfc9449#section-11.1). This applies to retries and to each poll of
+// the token endpoint.
+#[derive(Clone)]
+struct PreparedRequest {
+    endpoint: Endpoint,
+    url: Url,
+    headers: HeaderMap,
+    params: Vec<(String, String)>,
+    redirect_url: Option<String>,
+    client_authentication: Arc<dyn ClientAuthentication>,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    token_url: Option<TokenUrl>,
+    dpop_key: Option<DpopKey>,
+    config: HttpClientConfig,
+}
+
+impl PreparedRequest {
+    // Builds the request for a single attempt.
+    fn build<E: ErrorResponseType>(&self) -> Result<HttpRequest, RequestTokenError<E>> {
+        let mut headers = self.headers.clone();
+        let mut params: Vec<(&str, &str)> = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let context = ClientAuthenticationContext {
+            client_id: &self.client_id,
+            client_secret: self.client_secret.as_ref(),
+            token_url: self.token_url.as_ref(),
+            endpoint: self.endpoint,
+            url: &self.url,
+            client_certificate: self
+                .config
+                .client_certificate
+                .as_ref()
+                .map(|(certificate, _)| certificate),
+        };
+        let mut auth_params = Vec::new();
+        self.client_authentication
+            .authenticate(&context, &mut auth_params, &mut headers)
+            .map_err(|err| RequestTokenError::Other(err.to_string()))?;
+        params.extend(
+            auth_params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+
+        if let Some(ref redirect_url) = self.redirect_url {
+            params.push(("redirect_uri", redirect_url));
+        }
+
+        if let Some(ref dpop_key) = self.dpop_key {
+            let proof = dpop_key
+                .proof(&Method::POST, &self.url, None)
+                .map_err(|err| RequestTokenError::Other(err.to_string()))?;
+            headers.append(
+                DPOP,
+                // JWS compact serialization only contains valid header value characters.
+                HeaderValue::from_str(&proof).unwrap(),
+            );
+        }
+
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish()
+            .into_bytes();
+
+        Ok(HttpRequest {
+            url: self.url.clone(),
+            method: Method::POST,
+            headers,
+            body,
+            config: self.config.clone(),
+        })
+    }
+
+    // Remembers any DPoP nonce provided in the response, and returns whether the request must be
+    // sent again with a proof containing the nonce.
+    fn requires_dpop_nonce(&self, http_response: &HttpResponse) -> bool {
+        self.dpop_key
+            .as_ref()
+            .is_some_and(|dpop_key| dpop_key.handle_response(&self.url, http_response))
+    }
+}
+
+// Owned state needed for sending requests asynchronously, which allows the returned futures to
+// outlive the borrow of the `Client`.
+struct AsyncSender {
+    http_client: Option<Arc<dyn AsyncHttpClient>>,
+    retry_policy: Option<RetryPolicy>,
+    http_observers: Vec<Arc<dyn HttpObserver>>,
+}
+
+impl AsyncSender {
+    async fn send<T, E>(&self, request: &PreparedRequest) -> Result<T, RequestTokenError<E>>
+    where
+        T: DeserializeOwned,
+        E: ErrorResponseType,
+    {
+        parse_response(self.send_http_request(request).await?)
+    }
+
+    async fn send_http_request<E: ErrorResponseType>(
+        &self,
+        request: &PreparedRequest,
+    ) -> Result<HttpResponse, RequestTokenError<E>> {
+        let http_response = self.send_http_request_with_retries(request).await?;
+        if request.requires_dpop_nonce(&http_response) {
+            self.send_http_request_with_retries(request).await
+        } else {
+            Ok(http_response)
+        }
+    }
+
+    async fn send_http_request_with_retries<E: ErrorResponseType>(
+        &self,
+        request: &PreparedRequest,
+    ) -> Result<HttpResponse, RequestTokenError<E>> {
+        let http_client = self
+            .http_client
+            .as_ref()
+            .ok_or_else(|| RequestTokenError::Other("async_http_client must be set".to_string()))?;
+
+        let mut attempt = 1;
+        loop {
+            let http_request = request.build()?;
+            observe_request(&self.http_observers, &http_request);
+            let result = http_client.request_async(http_request).await;
+            observe_result(&self.http_observers, &result);
+            match self
+                .retry_policy
+                .as_ref()
+                .and_then(|policy| policy.retry_delay(attempt, &result))
+            {
+                Some(delay) => {
+                    Delay::new(delay).await;
+                    attempt += 1;
+                }
+                None => return Ok(result?),
+            }
+        }
+    }
+}
+
+impl<EF: ExtraTokenFields, TT: TokenType, TE: ErrorResponseType>
+    Client<EF, TT, StringScopeField, TE>
+{
+    /// Convert this client into one that uses a list-separated scope field.
+    pub fn with_list_scope(self) -> Client<EF, TT, ListScopeField, TE> {
+        Client {
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            auth_url: self.auth_url,
+            client_authentication: self.client_authentication,
+            endpoint_client_authentication: self.endpoint_client_authentication,
+            token_url: self.token_url,
+            device_authorization_url: self.device_authorization_url,
+            backchannel_authentication_url: self.backchannel_authentication_url,
+            introspection_url: self.introspection_url,
+            revocation_url: self.revocation_url,
+            mtls_endpoint_aliases: self.mtls_endpoint_aliases,
+            dpop_key: self.dpop_key,
+            scopes: self.scopes,
+            resources: self.resources,
+            authorization_details: self.authorization_details,
+            redirect_url: self.redirect_url,
+            http_client: self.http_client,
+            async_http_client: self.async_http_client,
+            http_config: self.http_config,
+            retry_policy: self.retry_policy,
+            http_observers: self.http_observers,
+            phantom_ef: self.phantom_ef,
+            phantom_tt: self.phantom_tt,
+            phantom_te: self.phantom_te,
+            phantom_sf: PhantomData,
+        }
+    }
+}
+
+// Tracks the state of polling the token endpoint while waiting for the end user to complete
+// authorization (e.g., during the Device Authorization Grant or CIBA flow).
+struct TokenPolling {
+    // `None` if the server-provided lifetime is too large to represent as an `Instant`, in which
+    // case the grant is treated as having no deadline.
+    deadline: Option<Instant>,
+    interval: Duration,
+    expired_message: &'static str,
+}
+
+impl TokenPolling {
+    fn new(expires_in: Duration, interval: Duration, expired_message: &'static str) -> Self {
+        TokenPolling {
+            deadline: Instant::now().checked_add(expires_in),
+            interval,
+            expired_message,
+        }
+    }
+
+    // Returns the delay before the next poll, or an error if the grant will have expired by then.
+    fn next_delay<E: ErrorResponseType>(&self) -> Result<Duration, RequestTokenError<E>> {
+        let next_poll = Instant::now().checked_add(self.interval).ok_or_else(|| {
+            RequestTokenError::Other("Server returned an unsupported polling interval".to_string())
+        })?;
+        match self.deadline {
+            Some(deadline) if next_poll > deadline => {
+                Err(RequestTokenError::Other(self.expired_message.to_string()))
+            }
+            _ => Ok(self.interval),
+        }
+    }
+
+    // Returns the final result of polling, or `None` if polling should continue.
+    fn handle<T, E: ErrorResponseType>(
+        &mut self,
+        result: Result<T, RequestTokenError<E>>,
+    ) -> Option<Result<T, RequestTokenError<E>>> {
+        if let Err(RequestTokenError::ServerResponse(ref error)) = result {
+            match error.error().polling_action() {
+                PollingAction::Continue => return None,
+                PollingAction::SlowDown => {
+                    // See https://tools.ietf.org/html/rfc8628#section-3.5.
+                    self.interval = self.interval.saturating_add(Duration::from_secs(5));
+                    return None;
+                }
+                PollingAction::Stop => {}
+            }
+        }
+        Some(result)
+    }
+}
+
+// Serializes the JSON array sent via the `authorization_details` parameter, or returns `None` if
+// no authorization details are specified.
+fn authorization_details_param(authorization_details: &[AuthorizationDetail]) -> Option<String> {
+    if authorization_details.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(authorization_details)
+                .expect("failed to serialize authorization details"),
+        )
+    }
+}
+
+// Parses the body of a non-successful response as an `ErrorResponse`.
+fn parse_error_response<TE: ErrorResponseType>(
+    http_response: &HttpResponse,
+) -> RequestTokenError<TE> {
+    let reason = http_response.body.as_slice();
+    if reason.is_empty() {
+        RequestTokenError::Other("Server returned empty error response".to_string())
+    } else {
+        match serde_json::from_slice::<ErrorResponse<TE>>(reason) {
+            Ok(error) => RequestTokenError::ServerResponse(error),
+            Err(error) => RequestTokenError::Parse(error, reason.to_vec()),
+        }
+    }
+}
+
+// The revocation endpoint indicates success using a `200 OK` status code, and the response
+// body (if any) is ignored (see https://tools.ietf.org/html/rfc7009#section-2.2).
+fn parse_revocation_response(
+    http_response: HttpResponse,
+) -> Result<(), RequestTokenError<RevocationErrorResponseType>> {
+    match http_response.status_code {
+        StatusCode::OK => Ok(()),
+        // See https://tools.ietf.org/html/rfc7009#section-2.2.1.
+        StatusCode::SERVICE_UNAVAILABLE => Err(RequestTokenError::ServiceUnavailable(
+            retry_after(&http_response),
+        )),
+        _ => Err(parse_error_response(&http_response)),
+    }
+}
+
+fn parse_response<T, TE>(http_response: HttpResponse) -> Result<T, RequestTokenError<TE>>
+where
+    T: DeserializeOwned,
+    TE: ErrorResponseType,
+{
+    if http_response.status_code != StatusCode::OK {
+        return Err(parse_error_response(&http_response));
+    }
+
+    // Validate that the response Content-Type is JSON.
+    http_response
+        .headers
+        .get(CONTENT_TYPE)
+        .map_or(Ok(()), |content_type| {
+            let content_type = String::from_utf8_lossy(content_type.as_bytes());
+            // Section 3.1.1.1 of RFC 7231 indicates that media types are case insensitive and
+            // may be followed by optional whitespace and/or a parameter (e.g., charset).
+            // See https://tools.ietf.org/html/rfc7231#section-3.1.1.1.
+            if !content_type.to_lowercase().starts_with(CONTENT_TYPE_JSON) {
+                Err(RequestTokenError::Other(format!(
+                    "Unexpected response Content-Type: `{}`, should be `{}`",
+                    content_type, CONTENT_TYPE_JSON
+                )))
+            } else {
+                Ok(())
+            }
+        })?;
+
+    if http_response.body.is_empty() {
+        Err(RequestTokenError::Other(
+            "Server returned empty response body".to_string(),
+        ))
+    } else {
+        let response_body = http_response.body.as_slice();
+        serde_json::from_slice(response_body)
+            .map_err(|e| RequestTokenError::Parse(e, response_body.to_vec()))
+    }
+}
+
+#[cfg(feature = "curl")]
+fn default_http_client() -> Option<Arc<dyn HttpClient>> {
+    Some(Arc::new(curl::CurlHttpClient::new()))
+}
+
+#[cfg(not(feature = "curl"))]
+fn default_http_client() -> Option<Arc<dyn HttpClient>> {
+    None
+}
+
+///
+/// An HTTP request sent to the authorization server.
+///
+#[derive(Clone)]
+pub struct HttpRequest {
+    /// URL to which the HTTP request is being made.
+    pub url: Url,
+    /// HTTP request method for this request.
+    pub method: Method,
+    /// HTTP request headers to send.
+    pub headers: HeaderMap,
+    /// HTTP request body (typically for POST requests only).
+    pub body: Vec<u8>,
+    /// Transport settings configured on the `Client` (e.g., timeouts and proxy), which the HTTP
+    /// client should apply when sending this request.
+    pub config: HttpClientConfig,
+}
+
+///
+/// Transport settings applied by an `HttpClient` or `AsyncHttpClient` to each request.
+///
+/// These settings are configured on the `Client` (e.g., via `Client::set_timeout`) and passed to
+/// the HTTP client as part of each `HttpRequest`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpClientConfig {
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<HttpProxy>,
+    no_proxy: Vec<String>,
+    root_certificates: Vec<CertificatePem>,
+    builtin_root_certificates: bool,
+    pinned_public_keys: Vec<SpkiSha256>,
+    client_certificate: Option<(CertificatePem, PrivateKeyPem)>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        HttpClientConfig {
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            no_proxy: Vec::new(),
+            root_certificates: Vec::new(),
+            builtin_root_certificates: true,
+            pinned_public_keys: Vec::new(),
+            client_certificate: None,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    ///
+    /// Maximum time allowed for establishing a connection, if any.
+    ///
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+    ///
+    /// Maximum time allowed for the entire request, if any.
+    ///
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+    ///
+    /// HTTP(S) proxy through which requests should be sent, if any.
+    ///
+    pub fn proxy(&self) -> Option<&HttpProxy> {
+        self.proxy.as_ref()
+    }
+    ///
+    /// Hosts (or domain suffixes) for which requests should bypass the proxy.
+    ///
+    pub fn no_proxy(&self) -> &[String] {
+        &self.no_proxy
+    }
+    ///
+    /// Additional root certificates trusted for verifying the server's TLS certificate.
+    ///
+    pub fn root_certificates(&self) -> &[CertificatePem] {
+        &self.root_certificates
+    }
+    ///
+    /// Whether the HTTP client's built-in root certificates are trusted in addition to
+    /// `root_certificates`.
+    ///
+    pub fn builtin_root_certificates(&self) -> bool {
+        self.builtin_root_certificates
+    }
+    ///
+    /// Public key pins, one of which must match the server's TLS public key if any are present.
+    ///
+    pub fn pinned_public_keys(&self) -> &[SpkiSha256] {
+        &self.pinned_public_keys
+    }
+    ///
+    /// Client certificate and private key used for mutual TLS, if any.
+    ///
+    pub fn client_certificate(&self) -> Option<(&CertificatePem, &PrivateKeyPem)> {
+        self.client_certificate
+            .as_ref()
+            .map(|(certificate, private_key)| (certificate, private_key))
+    }
+}
+
+///
+/// Policy for retrying token requests that fail due to transient errors.
+///
+/// A request is retried if sending it fails with `HttpClientError::Transport` or
+/// `HttpClientError::Timeout`, or the server responds with one of the following status codes:
+/// `429 Too Many Requests`, `502 Bad Gateway`, `503 Service Unavailable`, or
+/// `504 Gateway Timeout`.
+///
+/// Retries are delayed using exponential backoff with full jitter: the delay before the `n`th
+/// retry is chosen uniformly at random between zero and `initial_backoff * 2^(n - 1)`, capped at
+/// `max_backoff`. If the server includes a `Retry-After` header in its response, the request is
+/// retried after the requested delay instead. A `Retry-After` delay exceeding `max_retry_after`
+/// is treated as a permanent failure, and the response is returned without retrying.
+///
+/// See `Client::set_retry_policy`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+    ///
+    /// Creates a retry policy that sends each request at most `max_attempts` times (including
+    /// the initial attempt), with an initial backoff of 100 milliseconds, a maximum backoff of
+    /// 10 seconds, and a maximum `Retry-After` delay of 60 seconds.
+    ///
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+
+    ///
+    /// Sets the upper bound of the delay before the first retry, which doubles with each
+    /// subsequent retry.
+    ///
+    pub fn set_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+
+        self
+    }
+
+    ///
+    /// Sets the maximum delay between attempts.
+    ///
+    pub fn set_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+
+        self
+    }
+
+    ///
+    /// Sets the maximum delay requested by the server via a `Retry-After` header that is honored.
+    /// Responses requesting a longer delay are returned without retrying.
+    ///
+    pub fn set_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+
+        self
+    }
+
+    ///
+    /// Maximum number of times each request is sent, including the initial attempt.
+    ///
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    ///
+    /// Upper bound of the delay before the first retry.
+    ///
+    pub fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+    ///
+    /// Maximum delay between attempts.
+    ///
+    pub fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+    ///
+    /// Maximum delay requested via a `Retry-After` header that is honored.
+    ///
+    pub fn max_retry_after(&self) -> Duration {
+        self.max_retry_after
+    }
+
+    // Returns the delay before retrying the given (1-based) attempt, or `None` if the result
+    // should be returned to the caller.
+    fn retry_delay(
+        &self,
+        attempt: u32,
+        result: &Result<HttpResponse, HttpClientError>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match *result {
+            Ok(ref response) => match response.status_code {
+                StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT => {}
+                _ => return None,
+            },
+            Err(HttpClientError::Transport(_)) | Err(HttpClientError::Timeout) => {}
+            Err(_) => return None,
+        }
+
+        if let Some(retry_after) = result.as_ref().ok().and_then(retry_after) {
+            return if retry_after <= self.max_retry_after {
+                Some(retry_after)
+            } else {
+                None
+            };
+        }
+
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << (attempt - 1).min(31))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let max_millis = backoff.as_millis().min(u128::from(u64::MAX - 1)) as u64;
+        let jitter_millis = thread_rng().gen_range(0, max_millis + 1);
+        Some(Duration::from_millis(jitter_millis))
+    }
+}
+
+// Parses the `Retry-After` header, which contains either a number of seconds or an HTTP date.
+fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    let value = response.headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or_else(|_| Duration::from_secs(0)),
+    )
+}
+
+///
+/// HTTP(S) proxy used for sending requests to the authorization server.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpProxy {
+    url: Url,
+    credentials: Option<(String, ProxyPassword)>,
+}
+
+impl HttpProxy {
+    ///
+    /// Creates a proxy configuration for the given proxy URL (e.g., `http://proxy:3128`).
+    ///
+    pub fn new(url: Url) -> Self {
+        HttpProxy {
+            url,
+            credentials: None,
+        }
+    }
+
+    ///
+    /// Sets the username and password used for authenticating to the proxy.
+    ///
+    pub fn set_credentials(mut self, username: String, password: ProxyPassword) -> Self {
+        self.credentials = Some((username, password));
+
+        self
+    }
+
+    ///
+    /// URL of the proxy.
+    ///
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    ///
+    /// Username and password used for authenticating to the proxy, if any.
+    ///
+    pub fn credentials(&self) -> Option<(&str, &ProxyPassword)> {
+        self.credentials
+            .as_ref()
+            .map(|(username, password)| (username.as_str(), password))
+    }
+}
+
+///
+/// An HTTP response received from the authorization server.
+///
+#[derive(Clone)]
+pub struct HttpResponse {
+    /// HTTP status code returned by the server.
+    pub status_code: StatusCode,
+    /// HTTP response headers returned by the server.
+    pub headers: HeaderMap,
+    /// HTTP response body returned by the server.
+    pub body: Vec<u8>,
+}
+
+impl Debug for HttpRequest {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        let redacted = self.redacted();
+        f.debug_struct("HttpRequest")
+            .field("url", &redacted.url.as_str())
+            .field("method", &redacted.method)
+            .field("headers", &redacted.headers)
+            .field("body", &String::from_utf8_lossy(&redacted.body))
+            .field("config", &redacted.config)
+            .finish()
+    }
+}
+
+impl Debug for HttpResponse {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        let redacted = self.redacted();
+        f.debug_struct("HttpResponse")
+            .field("status_code", &redacted.status_code)
+            .field("headers", &redacted.headers)
+            .field("body", &String::from_utf8_lossy(&redacted.body))
+            .finish()
+    }
+}
+
+const REDACTED: &str = "[redacted]";
+
+// Form parameters containing secrets, which are redacted from observed requests.
+const SECRET_REQUEST_PARAMS: &[&str] = &[
+    "actor_token",
+    "assertion",
+    "auth_req_id",
+    "claim_token",
+    "client_assertion",
+    "client_secret",
+    "code",
+    "code_verifier",
+    "device_code",
+    "password",
+    "pct",
+    "refresh_token",
+    "rpt",
+    "subject_token",
+    "ticket",
+    "token",
+];
+
+// JSON response fields containing secrets, which are redacted from observed responses.
+const SECRET_RESPONSE_FIELDS: &[&str] = &[
+    "access_token",
+    "auth_req_id",
+    "device_code",
+    "id_token",
+    "pct",
+    "refresh_token",
+    "user_code",
+    "verification_uri_complete",
+];
+
+impl HttpRequest {
+    // Returns a copy of this request with any secrets (e.g., the client secret or authorization
+    // code) replaced by `[redacted]`.
+    fn redacted(&self) -> HttpRequest {
+        let mut redacted = self.clone();
+        // DPoP proofs are not secret, but they are redacted as well since each proof is unique.
+        for header_name in &[AUTHORIZATION, DPOP] {
+            if redacted.headers.contains_key(header_name) {
+                redacted
+                    .headers
+                    .insert(header_name, HeaderValue::from_static(REDACTED));
+            }
+        }
+
+        // Redact parameter values in place rather than re-encoding the form, which would also
+        // percent-encode the brackets in `[redacted]`.
+        let is_form = self
+            .headers
+            .get(CONTENT_TYPE)
+            .is_some_and(|content_type| content_type == CONTENT_TYPE_FORMENCODED);
+        if is_form {
+            redacted.body = self
+                .body
+                .split(|&b| b == b'&')
+                .map(|pair| {
+                    let name_len = pair.iter().position(|&b| b == b'=').unwrap_or(pair.len());
+                    let is_secret = form_urlencoded::parse(&pair[..name_len])
+                        .next()
+                        .is_some_and(|(name, _)| SECRET_REQUEST_PARAMS.contains(&&*name));
+                    if is_secret {
+                        [&pair[..name_len], b"=", REDACTED.as_bytes()].concat()
+                    } else {
+                        pair.to_vec()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(&b'&');
+        }
+        redacted
+    }
+}
+
+impl HttpResponse {
+    // Returns a copy of this response with any tokens replaced by `[redacted]`.
+    fn redacted(&self) -> HttpResponse {
+        let mut redacted = self.clone();
+        if let Ok(serde_json::Value::Object(mut fields)) =
+            serde_json::from_slice::<serde_json::Value>(&self.body)
+        {
+            let mut modified = false;
+            for name in SECRET_RESPONSE_FIELDS {
+                if let Some(value) = fields.get_mut(*name) {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                    modified = true;
+                }
+            }
+            if modified {
+                redacted.body = serde_json::to_vec(&fields).expect("failed to serialize JSON");
+            }
+        }
+        redacted
+    }
+}
+
+///
+/// Interface for observing the token requests sent to the authorization server and the
+/// responses received (e.g., for logging or debugging).
+///
+/// Observers receive copies of each request and response with any secrets (client secrets,
+/// authorization codes, PKCE code verifiers, passwords, and tokens, including the
+/// `Authorization` header) and DPoP proofs replaced by `[redacted]`. Each attempt made under a
+/// `RetryPolicy` is observed separately.
+///
+/// See `Client::add_http_observer`.
+///
+pub trait HttpObserver: Send + Sync {
+    ///
+    /// Called before a request is sent to the authorization server.
+    ///
+    fn on_request(&self, _request: &HttpRequest) {}
+
+    ///
+    /// Called after a response is received from the authorization server, regardless of its
+    /// status code.
+    ///
+    fn on_response(&self, _response: &HttpResponse) {}
+
+    ///
+    /// Called when the HTTP client fails to send a request or receive its response.
+    ///
+    fn on_error(&self, _error: &HttpClientError) {}
+}
+
+impl<O: HttpObserver + ?Sized> HttpObserver for Arc<O> {
+    fn on_request(&self, request: &HttpRequest) {
+        (**self).on_request(request)
+    }
+
+    fn on_response(&self, response: &HttpResponse) {
+        (**self).on_response(response)
+    }
+
+    fn on_error(&self, error: &HttpClientError) {
+        (**self).on_error(error)
+    }
+}
+
+impl Debug for dyn HttpObserver {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "HttpObserver")
+    }
+}
+
+fn observe_request(observers: &[Arc<dyn HttpObserver>], request: &HttpRequest) {
+    if observers.is_empty() {
+        return;
+    }
+    let redacted = request.redacted();
+    for observer in observers {
+        observer.on_request(&redacted);
+    }
+}
+
+fn observe_result(
+    observers: &[Arc<dyn HttpObserver>],
+    result: &Result<HttpResponse, HttpClientError>,
+) {
+    if observers.is_empty() {
+        return;
+    }
+    match *result {
+        Ok(ref response) => {
+            let redacted = response.redacted();
+            for observer in observers {
+                observer.on_response(&redacted);
+            }
+        }
+        Err(ref error) => {
+            for observer in observers {
+                observer.on_error(error);
+            }
+        }
+    }
+}
+
+///
+/// Interface for sending HTTP requests to the authorization server.
+///
+/// This trait is implemented for all functions and closures of the form
+/// `Fn(HttpRequest) -> Result<HttpResponse, HttpClientError>`.
+///
+pub trait HttpClient: Send + Sync {
+    ///
+    /// Sends the given HTTP request and returns the server's response.
+    ///
+    /// Implementations should return any response received from the server (including non-2xx
+    /// status codes) as `Ok(_)`, and should only return `Err(_)` if no response was received.
+    ///
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError>;
+}
+
+impl<F> HttpClient for F
+where
+    F: Fn(HttpRequest) -> Result<HttpResponse, HttpClientError> + Send + Sync,
+{
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        self(request)
+    }
+}
+
+impl Debug for dyn HttpClient {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "HttpClient")
+    }
+}
+
+///
+/// Future returned by an `AsyncHttpClient`.
+///
+pub type HttpClientFuture =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, HttpClientError>> + Send>>;
+
+///
+/// Interface for asynchronously sending HTTP requests to the authorization server.
+///
+/// This trait is implemented for all functions and closures of the form `Fn(HttpRequest) -> F`,
+/// where `F` is a `Future` resolving to `Result<HttpResponse, HttpClientError>`.
+///
+pub trait AsyncHttpClient: Send + Sync {
+    ///
+    /// Sends the given HTTP request and returns a future resolving to the server's response.
+    ///
+    /// As with `HttpClient`, implementations should resolve to `Ok(_)` for any response received
+    /// from the server (including non-2xx status codes).
+    ///
+    fn request_async(&self, request: HttpRequest) -> HttpClientFuture;
+}
+
+impl<F, R> AsyncHttpClient for F
+where
+    F: Fn(HttpRequest) -> R + Send + Sync,
+    R: Future<Output = Result<HttpResponse, HttpClientError>> + Send + 'static,
+{
+    fn request_async(&self, request: HttpRequest) -> HttpClientFuture {
+        Box::pin(self(request))
+    }
+}
+
+impl Debug for dyn AsyncHttpClient {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(f, "AsyncHttpClient")
+    }
+}
+
+///
+/// Error encountered by an `HttpClient` while sending a request.
+///
+#[derive(Debug)]
+pub enum HttpClientError {
+    ///
+    /// Error returned by the underlying HTTP transport (e.g., network connectivity failed).
+    ///
+    Transport(failure::Error),
+    ///
+    /// The request did not complete within the configured connect timeout or overall timeout.
+    ///
+    Timeout,
+    ///
+    /// Some other type of error occurred.
+    ///
+    Other(String),
+}
+
+// `Fail` is implemented by hand because `#[derive(Fail)]` triggers the `non_local_definitions`
+// lint, which can't be allowed for the derived impls without allowing it for the whole crate.
+impl Display for HttpClientError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        match *self {
+            HttpClientError::Transport(_) => write!(f, "Request failed"),
+            HttpClientError::Timeout => write!(f, "Request timed out"),
+            HttpClientError::Other(ref message) => write!(f, "Other error: {}", message),
+        }
+    }
+}
+
+impl failure::Fail for HttpClientError {
+    fn cause(&self) -> Option<&dyn failure::Fail> {
+        match *self {
+            HttpClientError::Transport(ref err) => Some(err.as_fail()),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Trait for OAuth2 access tokens.
+///
+pub trait TokenType: Clone + DeserializeOwned + Debug + PartialEq + Serialize {}
+
+///
+/// Trait for adding extra fields to the `TokenResponse`.
+///
+pub trait ExtraTokenFields: Clone + DeserializeOwned + Debug + PartialEq + Serialize {}
+
+///
+/// Empty (default) extra token fields.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EmptyExtraTokenFields {}
+impl ExtraTokenFields for EmptyExtraTokenFields {}
+
+/// Trait for implementing a scope field in TokenResponse.
+pub trait ScopeField:
+    Clone + Debug + DeserializeOwned + PartialEq + Serialize + Default + AsRef<Vec<Scope>>
+{
+}
+
+/// The default, standards-compliant scope field which uses a space-separated string.
+///
+/// This type takes a JSON string and splits at each space character into a `Vec<String>` .
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// use oauth2::{prelude::NewType, Scope, StringScopeField};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Token {
+///     scope: StringScopeField,
+/// }
+///
+/// # fn main() -> Result<(), Box<std::error::Error>> {
+/// let token = Token {
+///     scope: StringScopeField(vec![
+///         Scope::new(String::from("foo")),
+///         Scope::new(String::from("bar")),
+///         Scope::new(String::from("baz")),
+///     ]),
+/// };
+///
+/// let expected = "{\"scope\":\"foo bar baz\"}";
+/// assert_eq!(expected, serde_json::to_string(&token)?);
+/// assert_eq!(token, serde_json::from_str(expected)?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StringScopeField(pub Vec<Scope>);
+
+impl AsRef<Vec<Scope>> for StringScopeField {
+    fn as_ref(&self) -> &Vec<Scope> {
+        &self.0
+    }
+}
+
+impl ScopeField for StringScopeField {}
+
+impl<'de> serde::Deserialize<'de> for StringScopeField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        if let Some(space_delimited) = Option::<String>::deserialize(deserializer)? {
+            let entries = space_delimited
+                .split(' ')
+                .map(|s| Value::String(s.to_string()))
+                .collect();
+            Vec::<Scope>::deserialize(Value::Array(entries))
+                .map(StringScopeField)
+                .map_err(Error::custom)
+        } else {
+            // If the JSON value is null, use the default value.
+            Ok(StringScopeField(Default::default()))
+        }
+    }
+}
+
+impl serde::Serialize for StringScopeField {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let space_delimited = self
+            .0
+            .iter()
+            .map(|s| s.as_ref())
+            .collect::<Vec<_>>()
+            .join(" ");
+        serializer.serialize_str(&space_delimited)
+    }
+}
+
+/// List-based scope fields.
+///
+/// This is a non-standard representation for the `scope` field in the token response which is implemented by certain
+/// non-compliant OAuth 2.0 implementations (like Twitch).
+///
+/// You use this implementation by specifying it as you are constructing the client.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// use oauth2::{prelude::NewType, Scope, ListScopeField};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Token {
+///     scope: ListScopeField,
+/// }
+///
+/// # fn main() -> Result<(), Box<std::error::Error>> {
+/// let token = Token {
+///     scope: ListScopeField(vec![
+///         Scope::new(String::from("foo")),
+///         Scope::new(String::from("bar")),
+///         Scope::new(String::from("baz")),
+///     ]),
+/// };
+///
+/// let expected = "{\"scope\":[\"foo\",\"bar\",\"baz\"]}";
+/// assert_eq!(expected, serde_json::to_string(&token)?);
+/// assert_eq!(token, serde_json::from_str(expected)?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListScopeField(pub Vec<Scope>);
+
+impl AsRef<Vec<Scope>> for ListScopeField {
+    fn as_ref(&self) -> &Vec<Scope> {
+        &self.0
+    }
+}
+
+impl ScopeField for ListScopeField {}
+
+impl<'de> serde::Deserialize<'de> for ListScopeField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        if let Some(space_delimited) = Option::<Vec<String>>::deserialize(deserializer)? {
+            let entries = space_delimited.into_iter().map(Value::String).collect();
+            Vec::<Scope>::deserialize(Value::Array(entries))
+                .map(ListScopeField)
+                .map_err(Error::custom)
+        } else {
+            // If the JSON value is null, use the default value.
+            Ok(ListScopeField(Default::default()))
+        }
+    }
+}
+
+impl serde::Serialize for ListScopeField {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Vec::<Scope>::serialize(&self.0, serializer)
+    }
+}
+
+///
+/// Common methods shared by all OAuth2 token implementations.
+///
+/// The methods in this struct are defined in
+/// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1).
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TokenResponse<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> {
+    access_token: AccessToken,
+    #[serde(bound = "TT: TokenType")]
+    #[serde(deserialize_with = "helpers::deserialize_untagged_enum_case_insensitive")]
+    token_type: TT,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<RefreshToken>,
+    #[serde(bound = "SF: ScopeField")]
+    #[serde(rename = "scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    scopes: Option<SF>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    authorization_details: Option<Vec<AuthorizationDetail>>,
+    #[serde(rename = "cnf")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    confirmation: Option<Confirmation>,
+
+    #[serde(bound = "EF: ExtraTokenFields")]
+    #[serde(flatten)]
+    extra_fields: EF,
+}
+
+impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> TokenResponse<EF, TT, SF> {
+    ///
+    /// REQUIRED. The access token issued by the authorization server.
+    ///
+    pub fn access_token(&self) -> &AccessToken {
+        &self.access_token
+    }
+    ///
+    /// REQUIRED. The type of the token issued as described in
+    /// [Section 7.1](https://tools.ietf.org/html/rfc6749#section-7.1).
+    /// Value is case insensitive and deserialized to the generic `TokenType` parameter.
+    ///
+    pub fn token_type(&self) -> &TT {
+        &self.token_type
+    }
+    ///
+    /// RECOMMENDED. The lifetime in seconds of the access token. For example, the value 3600
+    /// denotes that the access token will expire in one hour from the time the response was
+    /// generated. If omitted, the authorization server SHOULD provide the expiration time via
+    /// other means or document the default value.
+    ///
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in.map(Duration::from_secs)
+    }
+    ///
+    /// OPTIONAL. The refresh token, which can be used to obtain new access tokens using the same
+    /// authorization grant as described in
+    /// [Section 6](https://tools.ietf.org/html/rfc6749#section-6).
+    ///
+    pub fn refresh_token(&self) -> Option<&RefreshToken> {
+        self.refresh_token.as_ref()
+    }
+    ///
+    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
+    /// scipe of the access token as described by
+    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
+    /// this space-delimited field is parsed into a `Vec` of individual scopes. If omitted from
+    /// the response, this field is `None`.
+    ///
+    pub fn scopes(&self) -> Option<&Vec<Scope>> {
+        self.scopes.as_ref().map(|s| s.as_ref())
+    }
+    ///
+    /// OPTIONAL. The authorization details granted by the authorization server, as described in
+    /// [Section 7 of RFC 9396](https://tools.ietf.org/html/rfc9396#section-7). If omitted from
+    /// the response, this field is `None`.
+    ///
+    pub fn authorization_details(&self) -> Option<&Vec<AuthorizationDetail>> {
+        self.authorization_details.as_ref()
+    }
+    ///
+    /// OPTIONAL. The confirmation method binding the access token to a key or certificate held
+    /// by the client (e.g., a certificate-bound access token as described in
+    /// [Section 3.1 of RFC 8705](https://tools.ietf.org/html/rfc8705#section-3.1)). If omitted
+    /// from the response, this field is `None`.
+    ///
+    pub fn confirmation(&self) -> Option<&Confirmation> {
+        self.confirmation.as_ref()
+    }
+
+    ///
+    /// Verifies that the access token is bound to the given PEM-encoded client certificate, as
+    /// indicated by the `x5t#S256` confirmation method.
+    ///
+    /// # Failures
+    /// Returns an error if the response does not include an `x5t#S256` confirmation method, if
+    /// the certificate cannot be parsed, or if its thumbprint does not match.
+    ///
+    pub fn verify_certificate_binding(
+        &self,
+        certificate: &CertificatePem,
+    ) -> Result<(), TokenBindingError> {
+        let expected = self
+            .confirmation
+            .as_ref()
+            .and_then(Confirmation::certificate_thumbprint)
+            .ok_or(TokenBindingError::Unbound)?;
+        let actual = CertificateThumbprint::from_certificate_pem(certificate)
+            .map_err(TokenBindingError::InvalidCertificate)?;
+        if actual != *expected {
+            return Err(TokenBindingError::Mismatch);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Extra fields defined by client application.
+    ///
+    pub fn extra_fields(&self) -> &EF {
+        &self.extra_fields
+    }
+
+    ///
+    /// Factory method to deserialize a `Token` from a JSON response.
+    ///
+    /// # Failures
+    /// If parsing fails, returns a `serde_json::error::Error` describing the parse error.
+    pub fn from_json(data: &str) -> Result<Self, serde_json::error::Error> {
+        serde_json::from_str(data)
+    }
+}
+
+///
+/// Confirmation claim (`cnf`) identifying the key or certificate to which a token is bound (see
+/// [RFC 7800](https://tools.ietf.org/html/rfc7800#section-3.1)).
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Confirmation {
+    #[serde(rename = "x5t#S256")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    certificate_thumbprint: Option<CertificateThumbprint>,
+    #[serde(rename = "jkt")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jwk_thumbprint: Option<String>,
+    #[serde(flatten)]
+    extra_fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Confirmation {
+    ///
+    /// Thumbprint of the client certificate to which the token is bound (`x5t#S256`), if any.
+    ///
+    pub fn certificate_thumbprint(&self) -> Option<&CertificateThumbprint> {
+        self.certificate_thumbprint.as_ref()
+    }
+    ///
+    /// JWK SHA-256 thumbprint of the DPoP key to which the token is bound (`jkt`), if any (see
+    /// [Section 6.1 of RFC 9449](https://tools.ietf.org/html/rfc9449#section-6.1)).
+    ///
+    pub fn jwk_thumbprint(&self) -> Option<&str> {
+        self.jwk_thumbprint.as_deref()
+    }
+    ///
+    /// Any other confirmation methods.
+    ///
+    pub fn extra_fields(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra_fields
+    }
+}
+
+///
+/// Authorization details object describing fine-grained permissions requested by the client or
+/// granted by the authorization server, as defined in
+/// [RFC 9396](https://tools.ietf.org/html/rfc9396#section-2).
+///
+/// The common data fields defined in
+/// [Section 2.2](https://tools.ietf.org/html/rfc9396#section-2.2) are available via typed
+/// accessors, while any fields specific to the authorization details `type` are preserved as
+/// extra fields. Applications may also convert between this type and their own serde-compatible
+/// types using `from_typed` and `to_typed`.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuthorizationDetail {
+    #[serde(rename = "type")]
+    detail_type: AuthorizationDetailType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    actions: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    datatypes: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identifier: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    privileges: Vec<String>,
+    #[serde(flatten)]
+    extra_fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AuthorizationDetail {
+    ///
+    /// Creates an authorization details object of the given type.
+    ///
+    pub fn new(detail_type: AuthorizationDetailType) -> Self {
+        AuthorizationDetail {
+            detail_type,
+            locations: Vec::new(),
+            actions: Vec::new(),
+            datatypes: Vec::new(),
+            identifier: None,
+            privileges: Vec::new(),
+            extra_fields: serde_json::Map::new(),
+        }
+    }
+
+    ///
+    /// Converts an application-defined type, which must serialize to a JSON object containing a
+    /// `type` field, into an authorization details object.
+    ///
+    pub fn from_typed<T: Serialize>(value: &T) -> Result<Self, serde_json::error::Error> {
+        serde_json::from_value(serde_json::to_value(value)?)
+    }
+
+    ///
+    /// Converts this authorization details object into an application-defined type.
+    ///
+    pub fn to_typed<T: DeserializeOwned>(&self) -> Result<T, serde_json::error::Error> {
+        serde_json::from_value(serde_json::to_value(self)?)
+    }
+
+    ///
+    /// Appends a location (e.g., the URI of a resource server) at which the requested access will
+    /// be used.
+    ///
+    pub fn add_location(mut self, location: String) -> Self {
+        self.locations.push(location);
+
+        self
+    }
+
+    ///
+    /// Appends a kind of action (e.g., `read`) to be taken at the resource.
+    ///
+    pub fn add_action(mut self, action: String) -> Self {
+        self.actions.push(action);
+
+        self
+    }
+
+    ///
+    /// Appends a kind of data being requested from the resource.
+    ///
+    pub fn add_datatype(mut self, datatype: String) -> Self {
+        self.datatypes.push(datatype);
+
+        self
+    }
+
+    ///
+    /// Sets the identifier of a specific resource available at the API.
+    ///
+    pub fn set_identifier(mut self, identifier: String) -> Self {
+        self.identifier = Some(identifier);
+
+        self
+    }
+
+    ///
+    /// Appends a privilege level (e.g., `admin`) being requested at the resource.
+    ///
+    pub fn add_privilege(mut self, privilege: String) -> Self {
+        self.privileges.push(privilege);
+
+        self
+    }
+
+    ///
+    /// Adds a field specific to this authorization details type.
+    ///
+    pub fn add_field(mut self, name: String, value: serde_json::Value) -> Self {
+        self.extra_fields.insert(name, value);
+
+        self
+    }
+
+    ///
+    /// REQUIRED. The type of this authorization details object.
+    ///
+    pub fn detail_type(&self) -> &AuthorizationDetailType {
+        &self.detail_type
+    }
+    ///
+    /// OPTIONAL. Locations at which the access will be used.
+    ///
+    pub fn locations(&self) -> &[String] {
+        &self.locations
+    }
+    ///
+    /// OPTIONAL. Kinds of actions to be taken at the resource.
+    ///
+    pub fn actions(&self) -> &[String] {
+        &self.actions
+    }
+    ///
+    /// OPTIONAL. Kinds of data being requested from the resource.
+    ///
+    pub fn datatypes(&self) -> &[String] {
+        &self.datatypes
+    }
+    ///
+    /// OPTIONAL. Identifier of a specific resource available at the API.
+    ///
+    pub fn identifier(&self) -> Option<&String> {
+        self.identifier.as_ref()
+    }
+    ///
+    /// OPTIONAL. Privilege levels being requested at the resource.
+    ///
+    pub fn privileges(&self) -> &[String] {
+        &self.privileges
+    }
+    ///
+    /// Fields specific to this authorization details type.
+    ///
+    pub fn extra_fields(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra_fields
+    }
+}
+
+///
+/// Error types enum.
+///
+/// NOTE: The implementation of the `Display` trait must return the `snake_case` representation of
+/// this error type. This value must match the error type from the relevant OAuth 2.0 standards
+/// (RFC 6749 or an extension).
+///
+pub trait ErrorResponseType:
+    Clone + Debug + DeserializeOwned + Display + PartialEq + Send + Serialize + Sync + 'static
 {
-    /// Convert this client into one that uses a list-separated scope field.
-    pub fn with_list_scope(self) -> Client<EF, TT, ListScopeField, TE> {
-        Client {
-            client_id: self.client_id,
-            client_secret: self.client_secret,
-            auth_url: self.auth_url,
-            auth_type: self.auth_type,
-            token_url: self.token_url,
-            scopes: self.scopes,
-            redirect_url: self.redirect_url,
-            phantom_ef: self.phantom_ef,
-            phantom_tt: self.phantom_tt,
-            phantom_te: self.phantom_te,
-            phantom_sf: PhantomData,
+    ///
+    /// Returns how to proceed when polling the token endpoint (e.g., during the Device
+    /// Authorization Grant or CIBA flow) fails with this error type.
+    ///
+    /// By default, polling stops and the error is returned.
+    ///
+    fn polling_action(&self) -> PollingAction {
+        PollingAction::Stop
+    }
+}
+
+///
+/// Action taken when polling the token endpoint fails with a given `ErrorResponseType`.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PollingAction {
+    ///
+    /// Continue polling at the current interval (e.g., for `authorization_pending` errors).
+    ///
+    Continue,
+    ///
+    /// Continue polling with the interval increased by 5 seconds (e.g., for `slow_down` errors).
+    ///
+    SlowDown,
+    ///
+    /// Stop polling and return the error.
+    ///
+    Stop,
+}
+
+///
+/// Parameters of a [JWT Bearer](https://tools.ietf.org/html/rfc7523#section-2.1) authorization
+/// grant request, from which a signed assertion is generated for each token request.
+///
+#[derive(Clone, Debug)]
+pub struct JwtBearerRequest {
+    signing_key: Arc<dyn JwsSigningKey>,
+    issuer: String,
+    subject: String,
+    audience: Option<String>,
+    expires_in: Duration,
+    extra_claims: serde_json::Map<String, serde_json::Value>,
+}
+
+impl JwtBearerRequest {
+    ///
+    /// Creates a request whose assertion is signed using `signing_key` and contains the given
+    /// `iss` (issuer) and `sub` (subject) claims.
+    ///
+    /// The assertion expires 5 minutes after it is issued unless `set_expires_in` is called.
+    ///
+    pub fn new<K>(signing_key: K, issuer: String, subject: String) -> Self
+    where
+        K: JwsSigningKey + 'static,
+    {
+        JwtBearerRequest {
+            signing_key: Arc::new(signing_key),
+            issuer,
+            subject,
+            audience: None,
+            expires_in: Duration::from_secs(300),
+            extra_claims: serde_json::Map::new(),
+        }
+    }
+
+    ///
+    /// Sets the `aud` (audience) claim, which defaults to the token endpoint URL.
+    ///
+    pub fn set_audience(mut self, audience: String) -> Self {
+        self.audience = Some(audience);
+
+        self
+    }
+
+    ///
+    /// Sets the lifetime of each assertion, which determines its `exp` (expiration time) claim.
+    ///
+    pub fn set_expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_in = expires_in;
+
+        self
+    }
+
+    ///
+    /// Adds a provider-specific claim to the assertion (e.g., a `scope` claim). Registered
+    /// claims set by this type (`iss`, `sub`, `aud`, `exp`, `iat` and `jti`) take precedence
+    /// over any extra claims with the same name.
+    ///
+    pub fn add_claim(mut self, name: String, value: serde_json::Value) -> Self {
+        self.extra_claims.insert(name, value);
+
+        self
+    }
+
+    fn assertion(&self, audience: &str) -> Result<String, jwt::JwtSigningError> {
+        let mut claims = self.extra_claims.clone();
+        claims.extend(jwt::assertion_claims(
+            &self.issuer,
+            &self.subject,
+            audience,
+            self.expires_in,
+        ));
+        jwt::encode(&*self.signing_key, &claims)
+    }
+}
+
+///
+/// Parameters of a [Token Exchange](https://tools.ietf.org/html/rfc8693#section-2.1) request.
+///
+#[derive(Clone, Debug)]
+pub struct TokenExchangeRequest {
+    subject_token: SubjectToken,
+    subject_token_type: TokenTypeIdentifier,
+    actor_token: Option<(ActorToken, TokenTypeIdentifier)>,
+    audiences: Vec<Audience>,
+    resources: Vec<ResourceUrl>,
+    requested_token_type: Option<TokenTypeIdentifier>,
+    scopes: Vec<Scope>,
+}
+
+impl TokenExchangeRequest {
+    ///
+    /// Creates a request to exchange the given subject token, which has the given token type.
+    ///
+    pub fn new(subject_token: SubjectToken, subject_token_type: TokenTypeIdentifier) -> Self {
+        TokenExchangeRequest {
+            subject_token,
+            subject_token_type,
+            actor_token: None,
+            audiences: Vec::new(),
+            resources: Vec::new(),
+            requested_token_type: None,
+            scopes: Vec::new(),
         }
     }
-}
 
-///
-/// Private struct returned by `post_request_token`.
-///
-struct RequestTokenResponse {
-    http_status: u32,
-    content_type: Option<String>,
-    response_body: Vec<u8>,
-}
+    ///
+    /// Sets the token representing the identity of the acting party, along with its type.
+    ///
+    pub fn set_actor_token(
+        mut self,
+        actor_token: ActorToken,
+        actor_token_type: TokenTypeIdentifier,
+    ) -> Self {
+        self.actor_token = Some((actor_token, actor_token_type));
 
-///
-/// Trait for OAuth2 access tokens.
-///
-pub trait TokenType: Clone + DeserializeOwned + Debug + PartialEq + Serialize {}
+        self
+    }
 
-///
-/// Trait for adding extra fields to the `TokenResponse`.
-///
-pub trait ExtraTokenFields: Clone + DeserializeOwned + Debug + PartialEq + Serialize {}
+    ///
+    /// Appends a logical name of a target service at which the requested token will be used.
+    ///
+    pub fn add_audience(mut self, audience: Audience) -> Self {
+        self.audiences.push(audience);
 
-///
-/// Empty (default) extra token fields.
-///
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct EmptyExtraTokenFields {}
-impl ExtraTokenFields for EmptyExtraTokenFields {}
+        self
+    }
 
-/// Trait for implementing a scope field in TokenResponse.
-pub trait ScopeField:
-    Clone + Debug + DeserializeOwned + PartialEq + Serialize + Default + AsRef<Vec<Scope>>
-{
+    ///
+    /// Appends the URI of a target service or resource at which the requested token will be
+    /// used.
+    ///
+    pub fn add_resource(mut self, resource: ResourceUrl) -> Self {
+        self.resources.push(resource);
+
+        self
+    }
+
+    ///
+    /// Sets the type of token being requested.
+    ///
+    pub fn set_requested_token_type(mut self, requested_token_type: TokenTypeIdentifier) -> Self {
+        self.requested_token_type = Some(requested_token_type);
+
+        self
+    }
+
+    ///
+    /// Appends a scope to request. If no scopes are added, the scopes configured on the `Client`
+    /// are requested instead.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+
+        self
+    }
 }
 
-/// The default, standards-compliant scope field which uses a space-separated string.
-///
-/// This type takes a JSON string and splits at each space character into a `Vec<String>` .
-///
-/// # Example
-///
-/// ```
-/// # #[macro_use] extern crate serde_derive;
-/// use oauth2::{prelude::NewType, Scope, StringScopeField};
 ///
-/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
-/// struct Token {
-///     scope: StringScopeField,
-/// }
+/// Successful response to a [Token Exchange](https://tools.ietf.org/html/rfc8693#section-2.2.1)
+/// request, which extends the standard token response with the type of the issued token.
 ///
-/// # fn main() -> Result<(), Box<std::error::Error>> {
-/// let token = Token {
-///     scope: StringScopeField(vec![
-///         Scope::new(String::from("foo")),
-///         Scope::new(String::from("bar")),
-///         Scope::new(String::from("baz")),
-///     ]),
-/// };
+/// Note that the issued token is returned in the `access_token` field even if it is not an OAuth
+/// 2.0 access token, in which case the server typically returns a `token_type` of `N_A`
+/// (see `BasicTokenType::NotApplicable`).
 ///
-/// let expected = "{\"scope\":\"foo bar baz\"}";
-/// assert_eq!(expected, serde_json::to_string(&token)?);
-/// assert_eq!(token, serde_json::from_str(expected)?);
-/// # Ok(())
-/// # }
-/// ```
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct StringScopeField(pub Vec<Scope>);
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TokenExchangeResponse<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> {
+    issued_token_type: TokenTypeIdentifier,
 
-impl AsRef<Vec<Scope>> for StringScopeField {
-    fn as_ref(&self) -> &Vec<Scope> {
-        &self.0
+    #[serde(bound = "EF: ExtraTokenFields, TT: TokenType, SF: ScopeField")]
+    #[serde(flatten)]
+    token: TokenResponse<EF, TT, SF>,
+}
+
+impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> TokenExchangeResponse<EF, TT, SF> {
+    ///
+    /// REQUIRED. The type of the issued token.
+    ///
+    pub fn issued_token_type(&self) -> &TokenTypeIdentifier {
+        &self.issued_token_type
+    }
+    ///
+    /// The standard token response fields, including the issued token.
+    ///
+    pub fn token(&self) -> &TokenResponse<EF, TT, SF> {
+        &self.token
+    }
+    ///
+    /// Converts this response into the standard token response, discarding the issued token
+    /// type.
+    ///
+    pub fn into_token(self) -> TokenResponse<EF, TT, SF> {
+        self.token
     }
 }
 
-impl ScopeField for StringScopeField {}
+///
+/// Token that may be introspected using `Client::introspect` or revoked using
+/// `Client::revoke_token`.
+///
+pub trait RevocableToken {
+    ///
+    /// The token value sent in the `token` parameter.
+    ///
+    fn token_secret(&self) -> &str;
 
-impl<'de> serde::Deserialize<'de> for StringScopeField {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        use serde::de::Error;
-        use serde_json::Value;
+    ///
+    /// The hint sent in the `token_type_hint` parameter when revoking the token, if any.
+    ///
+    fn token_type_hint(&self) -> Option<TokenTypeHint>;
+}
 
-        if let Some(space_delimited) = Option::<String>::deserialize(deserializer)? {
-            let entries = space_delimited
-                .split(' ')
-                .map(|s| Value::String(s.to_string()))
-                .collect();
-            Vec::<Scope>::deserialize(Value::Array(entries))
-                .map(StringScopeField)
-                .map_err(Error::custom)
-        } else {
-            // If the JSON value is null, use the default value.
-            Ok(StringScopeField(Default::default()))
-        }
+impl RevocableToken for AccessToken {
+    fn token_secret(&self) -> &str {
+        self.secret()
+    }
+
+    fn token_type_hint(&self) -> Option<TokenTypeHint> {
+        Some(TokenTypeHint::access_token())
     }
 }
 
-impl serde::Serialize for StringScopeField {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let space_delimited = self
-            .0
-            .iter()
-            .map(|s| s.as_ref())
-            .collect::<Vec<_>>()
-            .join(" ");
-        serializer.serialize_str(&space_delimited)
+impl RevocableToken for RefreshToken {
+    fn token_secret(&self) -> &str {
+        self.secret()
+    }
+
+    fn token_type_hint(&self) -> Option<TokenTypeHint> {
+        Some(TokenTypeHint::refresh_token())
     }
 }
 
-/// List-based scope fields.
-///
-/// This is a non-standard representation for the `scope` field in the token response which is implemented by certain
-/// non-compliant OAuth 2.0 implementations (like Twitch).
-///
-/// You use this implementation by specifying it as you are constructing the client.
-///
-/// # Example
-///
-/// ```
-/// # #[macro_use] extern crate serde_derive;
-/// use oauth2::{prelude::NewType, Scope, ListScopeField};
 ///
-/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
-/// struct Token {
-///     scope: ListScopeField,
-/// }
+/// Response returned by the token introspection endpoint, as described in
+/// [Section 2.2 of RFC 7662](https://tools.ietf.org/html/rfc7662#section-2.2).
 ///
-/// # fn main() -> Result<(), Box<std::error::Error>> {
-/// let token = Token {
-///     scope: ListScopeField(vec![
-///         Scope::new(String::from("foo")),
-///         Scope::new(String::from("bar")),
-///         Scope::new(String::from("baz")),
-///     ]),
-/// };
+/// Fields other than `active` are only meaningful for active tokens, and authorization servers
+/// typically omit them otherwise. Additional fields (e.g., those defined by a particular
+/// authorization server) are parsed as `EF`.
 ///
-/// let expected = "{\"scope\":[\"foo\",\"bar\",\"baz\"]}";
-/// assert_eq!(expected, serde_json::to_string(&token)?);
-/// assert_eq!(token, serde_json::from_str(expected)?);
-/// # Ok(())
-/// # }
-/// ```
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct ListScopeField(pub Vec<Scope>);
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IntrospectionResponse<EF: ExtraTokenFields> {
+    active: bool,
+    #[serde(rename = "scope")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scopes: Option<StringScopeField>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<ClientId>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_type: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iat: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nbf: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub: Option<String>,
+    #[serde(rename = "aud")]
+    #[serde(default)]
+    #[serde(deserialize_with = "helpers::deserialize_optional_string_or_vec")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audiences: Option<Vec<Audience>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jti: Option<String>,
+    #[serde(rename = "cnf")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confirmation: Option<Confirmation>,
 
-impl AsRef<Vec<Scope>> for ListScopeField {
-    fn as_ref(&self) -> &Vec<Scope> {
-        &self.0
-    }
+    #[serde(bound = "EF: ExtraTokenFields")]
+    #[serde(flatten)]
+    extra_fields: EF,
 }
 
-impl ScopeField for ListScopeField {}
-
-impl<'de> serde::Deserialize<'de> for ListScopeField {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        use serde::de::Error;
-        use serde_json::Value;
-
-        if let Some(space_delimited) = Option::<Vec<String>>::deserialize(deserializer)? {
-            let entries = space_delimited.into_iter().map(Value::String).collect();
-            Vec::<Scope>::deserialize(Value::Array(entries))
-                .map(ListScopeField)
-                .map_err(Error::custom)
-        } else {
-            // If the JSON value is null, use the default value.
-            Ok(ListScopeField(Default::default()))
-        }
+impl<EF: ExtraTokenFields> IntrospectionResponse<EF> {
+    ///
+    /// REQUIRED. Whether the token is currently active.
+    ///
+    pub fn active(&self) -> bool {
+        self.active
+    }
+    ///
+    /// OPTIONAL. The scopes associated with the token.
+    ///
+    pub fn scopes(&self) -> Option<&Vec<Scope>> {
+        self.scopes.as_ref().map(|s| s.as_ref())
+    }
+    ///
+    /// OPTIONAL. The client to which the token was issued.
+    ///
+    pub fn client_id(&self) -> Option<&ClientId> {
+        self.client_id.as_ref()
+    }
+    ///
+    /// OPTIONAL. Human-readable identifier of the resource owner who authorized the token.
+    ///
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+    ///
+    /// OPTIONAL. The type of the token (e.g., `Bearer`).
+    ///
+    pub fn token_type(&self) -> Option<&str> {
+        self.token_type.as_deref()
+    }
+    ///
+    /// OPTIONAL. The time at which the token expires.
+    ///
+    /// The `exp`, `iat` and `nbf` timestamps are `None` if they are too large to represent as a
+    /// `SystemTime`.
+    ///
+    pub fn exp(&self) -> Option<SystemTime> {
+        self.exp.and_then(timestamp)
+    }
+    ///
+    /// OPTIONAL. The time at which the token was issued.
+    ///
+    pub fn iat(&self) -> Option<SystemTime> {
+        self.iat.and_then(timestamp)
+    }
+    ///
+    /// OPTIONAL. The time before which the token must not be used.
+    ///
+    pub fn nbf(&self) -> Option<SystemTime> {
+        self.nbf.and_then(timestamp)
+    }
+    ///
+    /// OPTIONAL. The subject of the token (typically a machine-readable identifier of the
+    /// resource owner).
+    ///
+    pub fn sub(&self) -> Option<&str> {
+        self.sub.as_deref()
+    }
+    ///
+    /// OPTIONAL. The intended audiences of the token.
+    ///
+    pub fn audiences(&self) -> Option<&Vec<Audience>> {
+        self.audiences.as_ref()
+    }
+    ///
+    /// OPTIONAL. The issuer of the token.
+    ///
+    pub fn iss(&self) -> Option<&str> {
+        self.iss.as_deref()
+    }
+    ///
+    /// OPTIONAL. The unique identifier of the token.
+    ///
+    pub fn jti(&self) -> Option<&str> {
+        self.jti.as_deref()
+    }
+    ///
+    /// OPTIONAL. The key or certificate to which the token is bound (e.g., for DPoP-bound or
+    /// certificate-bound access tokens).
+    ///
+    pub fn confirmation(&self) -> Option<&Confirmation> {
+        self.confirmation.as_ref()
+    }
+    ///
+    /// Extra fields defined by the authorization server.
+    ///
+    pub fn extra_fields(&self) -> &EF {
+        &self.extra_fields
     }
 }
 
-impl serde::Serialize for ListScopeField {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        Vec::<Scope>::serialize(&self.0, serializer)
-    }
+// Returns `None` if the timestamp is too large to represent as a `SystemTime`.
+fn timestamp(secs: u64) -> Option<SystemTime> {
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
 }
 
 ///
-/// Common methods shared by all OAuth2 token implementations.
-///
-/// The methods in this struct are defined in
-/// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1).
+/// Response returned by the device authorization endpoint, as described in
+/// [Section 3.2 of RFC 8628](https://tools.ietf.org/html/rfc8628#section-3.2).
 ///
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct TokenResponse<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> {
-    access_token: AccessToken,
-    #[serde(bound = "TT: TokenType")]
-    #[serde(deserialize_with = "helpers::deserialize_untagged_enum_case_insensitive")]
-    token_type: TT,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    expires_in: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    refresh_token: Option<RefreshToken>,
-    #[serde(bound = "SF: ScopeField")]
-    #[serde(rename = "scope")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeviceAuthorizationResponse {
+    device_code: DeviceCode,
+    user_code: UserCode,
+    // Some providers (e.g., Google) use the field name from earlier drafts of the RFC.
+    #[serde(alias = "verification_url")]
+    verification_uri: EndUserVerificationUrl,
     #[serde(default)]
-    scopes: Option<SF>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification_uri_complete: Option<VerificationUriComplete>,
+    expires_in: u64,
+    #[serde(default = "default_polling_interval")]
+    interval: u64,
+}
 
-    #[serde(bound = "EF: ExtraTokenFields")]
-    #[serde(flatten)]
-    extra_fields: EF,
+fn default_polling_interval() -> u64 {
+    5
 }
 
-impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> TokenResponse<EF, TT, SF> {
+impl DeviceAuthorizationResponse {
     ///
-    /// REQUIRED. The access token issued by the authorization server.
+    /// REQUIRED. The device verification code.
     ///
-    pub fn access_token(&self) -> &AccessToken {
-        &self.access_token
+    pub fn device_code(&self) -> &DeviceCode {
+        &self.device_code
     }
     ///
-    /// REQUIRED. The type of the token issued as described in
-    /// [Section 7.1](https://tools.ietf.org/html/rfc6749#section-7.1).
-    /// Value is case insensitive and deserialized to the generic `TokenType` parameter.
+    /// REQUIRED. The end-user verification code.
     ///
-    pub fn token_type(&self) -> &TT {
-        &self.token_type
+    pub fn user_code(&self) -> &UserCode {
+        &self.user_code
     }
     ///
-    /// RECOMMENDED. The lifetime in seconds of the access token. For example, the value 3600
-    /// denotes that the access token will expire in one hour from the time the response was
-    /// generated. If omitted, the authorization server SHOULD provide the expiration time via
-    /// other means or document the default value.
+    /// REQUIRED. The end-user verification URI on the authorization server.
     ///
-    pub fn expires_in(&self) -> Option<Duration> {
-        self.expires_in.map(Duration::from_secs)
+    pub fn verification_uri(&self) -> &EndUserVerificationUrl {
+        &self.verification_uri
     }
     ///
-    /// OPTIONAL. The refresh token, which can be used to obtain new access tokens using the same
-    /// authorization grant as described in
-    /// [Section 6](https://tools.ietf.org/html/rfc6749#section-6).
+    /// OPTIONAL. A verification URI that includes the user code, designed for non-textual
+    /// transmission.
     ///
-    pub fn refresh_token(&self) -> Option<&RefreshToken> {
-        self.refresh_token.as_ref()
+    pub fn verification_uri_complete(&self) -> Option<&VerificationUriComplete> {
+        self.verification_uri_complete.as_ref()
     }
     ///
-    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
-    /// scipe of the access token as described by
-    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
-    /// this space-delimited field is parsed into a `Vec` of individual scopes. If omitted from
-    /// the response, this field is `None`.
+    /// REQUIRED. The lifetime of the device code and user code.
     ///
-    pub fn scopes(&self) -> Option<&Vec<Scope>> {
-        self.scopes.as_ref().map(|s| s.as_ref())
+    pub fn expires_in(&self) -> Duration {
+        Duration::from_secs(self.expires_in)
+    }
+    ///
+    /// OPTIONAL. The minimum amount of time that the client should wait between polling
+    /// requests to the token endpoint. Defaults to 5 seconds.
+    ///
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
     }
+}
+
+///
+/// Parameters of a [CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#auth_request)
+/// authentication request sent to the backchannel authentication endpoint.
+///
+#[derive(Clone, Debug)]
+pub struct BackchannelAuthenticationRequest {
+    login_hint: LoginHint,
+    binding_message: Option<BindingMessage>,
+    scopes: Vec<Scope>,
+}
 
+impl BackchannelAuthenticationRequest {
     ///
-    /// Extra fields defined by client application.
+    /// Creates an authentication request for the end user identified by `login_hint`.
     ///
-    pub fn extra_fields(&self) -> &EF {
-        &self.extra_fields
+    pub fn new(login_hint: LoginHint) -> Self {
+        BackchannelAuthenticationRequest {
+            login_hint,
+            binding_message: None,
+            scopes: Vec::new(),
+        }
     }
 
     ///
-    /// Factory method to deserialize a `Token` from a JSON response.
+    /// Sets the message displayed on both the consumption device and the authentication device.
     ///
-    /// # Failures
-    /// If parsing fails, returns a `serde_json::error::Error` describing the parse error.
-    pub fn from_json(data: &str) -> Result<Self, serde_json::error::Error> {
-        serde_json::from_str(data)
+    pub fn set_binding_message(mut self, binding_message: BindingMessage) -> Self {
+        self.binding_message = Some(binding_message);
+
+        self
+    }
+
+    ///
+    /// Appends a scope to the request. CIBA requests must include the `openid` scope.
+    ///
+    /// If no scopes are added to the request, the scopes configured on the `Client` are used.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+
+        self
     }
 }
 
 ///
-/// Error types enum.
-///
-/// NOTE: The implementation of the `Display` trait must return the `snake_case` representation of
-/// this error type. This value must match the error type from the relevant OAuth 2.0 standards
-/// (RFC 6749 or an extension).
+/// Response returned by the backchannel authentication endpoint, as described in
+/// [Section 7.3 of OpenID Connect CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#successful_authentication_request_acknowdlegment).
 ///
-pub trait ErrorResponseType:
-    Clone + Debug + DeserializeOwned + Display + PartialEq + Send + Serialize + Sync
-{
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BackchannelAuthenticationResponse {
+    auth_req_id: AuthenticationRequestId,
+    expires_in: u64,
+    #[serde(default = "default_polling_interval")]
+    interval: u64,
+}
+
+impl BackchannelAuthenticationResponse {
+    ///
+    /// REQUIRED. Identifier of the authentication request, used when polling the token
+    /// endpoint.
+    ///
+    pub fn auth_req_id(&self) -> &AuthenticationRequestId {
+        &self.auth_req_id
+    }
+    ///
+    /// REQUIRED. The lifetime of the authentication request.
+    ///
+    pub fn expires_in(&self) -> Duration {
+        Duration::from_secs(self.expires_in)
+    }
+    ///
+    /// OPTIONAL. The minimum amount of time that the client should wait between polling
+    /// requests to the token endpoint. Defaults to 5 seconds.
+    ///
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
 }
 
 ///
@@ -1556,7 +5055,12 @@ pub enum RequestTokenError<T: ErrorResponseType + Send + Sync + 'static> {
     /// connectivity failed).
     ///
     #[fail(display = "Request failed")]
-    Request(#[cause] curl::Error),
+    Request(#[cause] HttpClientError),
+    ///
+    /// The request did not complete within the timeout configured on the `Client`.
+    ///
+    #[fail(display = "Request timed out")]
+    Timeout,
     ///
     /// Failed to parse server response. Parse errors may occur while parsing either successful
     /// or error responses.
@@ -1564,12 +5068,64 @@ pub enum RequestTokenError<T: ErrorResponseType + Send + Sync + 'static> {
     #[fail(display = "Failed to parse server response")]
     Parse(#[cause] serde_json::error::Error, Vec<u8>),
     ///
+    /// The server is temporarily unable to handle the request (`503 Service Unavailable`), and
+    /// any retries have been exhausted. Contains the delay requested via the response's
+    /// `Retry-After` header, if any, after which the request may be sent again.
+    ///
+    /// This is currently only returned for token revocation requests (see
+    /// [Section 2.2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.2.1)).
+    ///
+    #[fail(display = "Server temporarily unavailable")]
+    ServiceUnavailable(Option<Duration>),
+    ///
     /// Some other type of error occurred (e.g., an unexpected server response).
     ///
     #[fail(display = "Other error: {}", _0)]
     Other(String),
 }
 
+impl<T: ErrorResponseType> From<HttpClientError> for RequestTokenError<T> {
+    fn from(err: HttpClientError) -> Self {
+        match err {
+            HttpClientError::Timeout => RequestTokenError::Timeout,
+            err => RequestTokenError::Request(err),
+        }
+    }
+}
+
+///
+/// Error encountered while verifying that an access token is bound to a particular key or
+/// certificate.
+///
+#[derive(Debug)]
+pub enum TokenBindingError {
+    ///
+    /// The token response does not indicate that the token is bound to a certificate.
+    ///
+    Unbound,
+    ///
+    /// The token is bound to a different certificate.
+    ///
+    Mismatch,
+    ///
+    /// The certificate could not be parsed.
+    ///
+    InvalidCertificate(String),
+}
+
+// Implemented by hand for the same reason as `HttpClientError`.
+impl Display for TokenBindingError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        match *self {
+            TokenBindingError::Unbound => write!(f, "Token is not bound to a certificate"),
+            TokenBindingError::Mismatch => write!(f, "Token is bound to a different certificate"),
+            TokenBindingError::InvalidCertificate(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl failure::Fail for TokenBindingError {}
+
 ///
 /// Basic OAuth2 implementation with no extensions
 /// ([RFC 6749](https://tools.ietf.org/html/rfc6749)).
@@ -1582,8 +5138,8 @@ pub mod basic {
 
     use super::helpers;
     use super::{
-        Client, EmptyExtraTokenFields, ErrorResponse, ErrorResponseType, RequestTokenError,
-        StringScopeField, TokenResponse, TokenType,
+        Client, EmptyExtraTokenFields, ErrorResponse, ErrorResponseType, IntrospectionResponse,
+        PollingAction, RequestTokenError, StringScopeField, TokenResponse, TokenType,
     };
 
     ///
@@ -1608,6 +5164,19 @@ pub mod basic {
         /// Tokens](https://tools.ietf.org/html/draft-ietf-oauth-v2-http-mac-05)).
         ///
         Mac,
+        ///
+        /// Not applicable (`N_A`), returned by the
+        /// [Token Exchange](https://tools.ietf.org/html/rfc8693#section-2.2.1) grant when the
+        /// issued token is not an access token.
+        ///
+        #[serde(rename = "N_A", alias = "n_a")]
+        NotApplicable,
+        ///
+        /// DPoP-bound access token
+        /// ([OAuth 2.0 Demonstrating Proof of Possession (DPoP) - RFC 9449](https://tools.ietf.org/html/rfc9449)).
+        ///
+        #[serde(rename = "DPoP", alias = "dpop")]
+        DPoP,
     }
     impl TokenType for BasicTokenType {}
 
@@ -1617,6 +5186,11 @@ pub mod basic {
     pub type BasicTokenResponse =
         TokenResponse<EmptyExtraTokenFields, BasicTokenType, StringScopeField>;
 
+    ///
+    /// Basic token introspection response.
+    ///
+    pub type BasicIntrospectionResponse = IntrospectionResponse<EmptyExtraTokenFields>;
+
     ///
     /// Basic access token error types.
     ///
@@ -1672,6 +5246,178 @@ pub mod basic {
         }
     }
 
+    ///
+    /// Access token error types returned while polling the token endpoint during the
+    /// [Device Authorization Grant](https://tools.ietf.org/html/rfc8628).
+    ///
+    /// These error types are defined in
+    /// [Section 3.5 of RFC 8628](https://tools.ietf.org/html/rfc8628#section-3.5), in addition to
+    /// the basic error types defined in
+    /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+    ///
+    #[derive(Clone, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum DeviceCodeErrorResponseType {
+        ///
+        /// The authorization request is still pending as the end user hasn't yet completed the
+        /// user-interaction steps.
+        ///
+        AuthorizationPending,
+        ///
+        /// The authorization request is still pending, and the client should increase the
+        /// polling interval by 5 seconds.
+        ///
+        SlowDown,
+        ///
+        /// The authorization request was denied.
+        ///
+        AccessDenied,
+        ///
+        /// The device code has expired, and the device authorization session has concluded.
+        ///
+        ExpiredToken,
+        ///
+        /// A basic error type defined in
+        /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+        ///
+        #[serde(untagged)]
+        Basic(BasicErrorResponseType),
+    }
+
+    impl ErrorResponseType for DeviceCodeErrorResponseType {
+        fn polling_action(&self) -> PollingAction {
+            match *self {
+                DeviceCodeErrorResponseType::AuthorizationPending => PollingAction::Continue,
+                DeviceCodeErrorResponseType::SlowDown => PollingAction::SlowDown,
+                _ => PollingAction::Stop,
+            }
+        }
+    }
+
+    impl Debug for DeviceCodeErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            Display::fmt(self, f)
+        }
+    }
+
+    impl Display for DeviceCodeErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            match *self {
+                DeviceCodeErrorResponseType::Basic(ref basic) => Display::fmt(basic, f),
+                _ => write!(f, "{}", helpers::variant_name(&self)),
+            }
+        }
+    }
+
+    ///
+    /// Access token error types returned while polling the token endpoint during the
+    /// [Client-Initiated Backchannel Authentication](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html)
+    /// (CIBA) flow.
+    ///
+    /// These error types are defined in
+    /// [Section 11 of OpenID Connect CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.11),
+    /// in addition to the basic error types defined in
+    /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+    ///
+    #[derive(Clone, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum CibaErrorResponseType {
+        ///
+        /// The authorization request is still pending as the end user hasn't yet been
+        /// authenticated.
+        ///
+        AuthorizationPending,
+        ///
+        /// The authorization request is still pending, and the client should increase the
+        /// polling interval by at least 5 seconds.
+        ///
+        SlowDown,
+        ///
+        /// The end user denied the authorization request.
+        ///
+        AccessDenied,
+        ///
+        /// The `auth_req_id` has expired, and the client must make a new authentication request.
+        ///
+        ExpiredToken,
+        ///
+        /// The OpenID provider encountered an unexpected condition that prevented it from
+        /// successfully completing the transaction.
+        ///
+        TransactionFailed,
+        ///
+        /// A basic error type defined in
+        /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+        ///
+        #[serde(untagged)]
+        Basic(BasicErrorResponseType),
+    }
+
+    impl ErrorResponseType for CibaErrorResponseType {
+        fn polling_action(&self) -> PollingAction {
+            match *self {
+                CibaErrorResponseType::AuthorizationPending => PollingAction::Continue,
+                CibaErrorResponseType::SlowDown => PollingAction::SlowDown,
+                _ => PollingAction::Stop,
+            }
+        }
+    }
+
+    impl Debug for CibaErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            Display::fmt(self, f)
+        }
+    }
+
+    impl Display for CibaErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            match *self {
+                CibaErrorResponseType::Basic(ref basic) => Display::fmt(basic, f),
+                _ => write!(f, "{}", helpers::variant_name(&self)),
+            }
+        }
+    }
+
+    ///
+    /// Error types returned by the token revocation endpoint.
+    ///
+    /// These error types are defined in
+    /// [Section 2.2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.2.1), in
+    /// addition to the basic error types defined in
+    /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+    ///
+    #[derive(Clone, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum RevocationErrorResponseType {
+        ///
+        /// The authorization server does not support the revocation of the presented token type.
+        ///
+        UnsupportedTokenType,
+        ///
+        /// A basic error type defined in
+        /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+        ///
+        #[serde(untagged)]
+        Basic(BasicErrorResponseType),
+    }
+
+    impl ErrorResponseType for RevocationErrorResponseType {}
+
+    impl Debug for RevocationErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            Display::fmt(self, f)
+        }
+    }
+
+    impl Display for RevocationErrorResponseType {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+            match *self {
+                RevocationErrorResponseType::Basic(ref basic) => Display::fmt(basic, f),
+                _ => write!(f, "{}", helpers::variant_name(&self)),
+            }
+        }
+    }
+
     ///
     /// Error response specialization for basic OAuth2 implementation.
     ///
@@ -1739,6 +5485,7 @@ pub mod insecure {
 pub mod helpers {
     use std;
 
+    use serde::de::DeserializeOwned;
     use serde::ser;
     use serde::ser::{Impossible, SerializeStructVariant, SerializeTupleVariant};
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -1797,6 +5544,31 @@ pub mod helpers {
         .map_err(Error::custom)
     }
 
+    ///
+    /// Serde deserializer for an optional value that may be either a single string or an array
+    /// of strings (e.g., the `aud` claim), which is always deserialized as a `Vec`.
+    ///
+    pub fn deserialize_optional_string_or_vec<'de, T, D>(
+        deserializer: D,
+    ) -> Result<Option<Vec<T>>, D::Error>
+    where
+        T: DeserializeOwned,
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        match Option::<Value>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(value @ Value::String(_)) => {
+                Ok(Some(vec![T::deserialize(value).map_err(Error::custom)?]))
+            }
+            Some(value) => Vec::<T>::deserialize(value)
+                .map(Some)
+                .map_err(Error::custom),
+        }
+    }
+
     ///
     /// Serde string deserializer for a `Url`.
     ///
@@ -0,0 +1,323 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use reqwest::redirect::Policy;
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use reqwest::{Certificate, Identity};
+use reqwest::{NoProxy, Proxy};
+
+use super::prelude::*;
+use super::{
+    AsyncHttpClient, HttpClient, HttpClientConfig, HttpClientError, HttpClientFuture, HttpRequest,
+    HttpResponse,
+};
+
+// Applies the settings shared by `reqwest::ClientBuilder` and `reqwest::blocking::ClientBuilder`,
+// which have identical methods but no common trait.
+macro_rules! configure_builder {
+    ($builder:expr, $config:expr) => {{
+        let config: &HttpClientConfig = $config;
+        check_tls_support(config)?;
+
+        let mut builder = $builder.redirect(Policy::none());
+        if let Some(connect_timeout) = config.connect_timeout() {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        for proxy in proxies(config)? {
+            builder = builder.proxy(proxy);
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        {
+            builder = builder.tls_built_in_root_certs(config.builtin_root_certificates());
+            for certificate in root_certificates(config)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+            if let Some(identity) = identity(config)? {
+                builder = builder.identity(identity);
+            }
+        }
+        builder
+    }};
+}
+
+///
+/// Synchronous HTTP client backed by [reqwest](https://crates.io/crates/reqwest)'s blocking API.
+///
+/// The underlying `reqwest::blocking::Client` is reused across requests as long as the
+/// `HttpClientConfig` remains unchanged. Note that, as with any `reqwest::blocking::Client`, this
+/// type must not be used from within an async runtime. Use `AsyncReqwestHttpClient` in that case.
+///
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestHttpClient {
+    client: Arc<Mutex<Option<(HttpClientConfig, reqwest::blocking::Client)>>>,
+    provided_client: Option<reqwest::blocking::Client>,
+}
+
+impl ReqwestHttpClient {
+    ///
+    /// Creates a new HTTP client that does not follow redirects.
+    ///
+    /// Following redirects would make it possible for a malicious authorization server to direct
+    /// token requests (including client credentials) to an arbitrary host.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn client(
+        &self,
+        config: &HttpClientConfig,
+    ) -> Result<reqwest::blocking::Client, HttpClientError> {
+        if let Some(ref client) = self.provided_client {
+            return Ok(client.clone());
+        }
+
+        let mut cached = self.client.lock().unwrap();
+        if let Some((ref cached_config, ref client)) = *cached {
+            if cached_config == config {
+                return Ok(client.clone());
+            }
+        }
+
+        let client = configure_builder!(reqwest::blocking::Client::builder(), config)
+            // Disable the blocking client's default timeout in favor of the configured one.
+            .timeout(config.timeout())
+            .build()?;
+
+        *cached = Some((config.clone(), client.clone()));
+        Ok(client)
+    }
+}
+
+///
+/// Wraps an existing `reqwest::blocking::Client`, which is used for all requests as is.
+///
+/// The `HttpClientConfig` of each request (e.g., timeouts, proxy, and TLS settings) is ignored,
+/// so these settings must be configured on the `reqwest::blocking::Client` instead. The client
+/// should be configured not to follow redirects (see `ReqwestHttpClient::new`).
+///
+impl From<reqwest::blocking::Client> for ReqwestHttpClient {
+    fn from(client: reqwest::blocking::Client) -> Self {
+        ReqwestHttpClient {
+            client: Arc::default(),
+            provided_client: Some(client),
+        }
+    }
+}
+
+impl HttpClient for ReqwestHttpClient {
+    fn request(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+        let response = self
+            .client(&request.config)?
+            .request(request.method, request.url.as_str())
+            .headers(request.headers)
+            .body(request.body)
+            .send()?;
+
+        Ok(HttpResponse {
+            status_code: response.status(),
+            headers: response.headers().clone(),
+            body: response.bytes()?.to_vec(),
+        })
+    }
+}
+
+///
+/// Asynchronous HTTP client backed by [reqwest](https://crates.io/crates/reqwest).
+///
+/// The underlying `reqwest::Client` is reused across requests as long as the `HttpClientConfig`
+/// remains unchanged.
+///
+#[derive(Clone, Debug, Default)]
+pub struct AsyncReqwestHttpClient {
+    client: Arc<Mutex<Option<(HttpClientConfig, reqwest::Client)>>>,
+    provided_client: Option<reqwest::Client>,
+}
+
+impl AsyncReqwestHttpClient {
+    ///
+    /// Creates a new asynchronous HTTP client that does not follow redirects.
+    ///
+    /// Following redirects would make it possible for a malicious authorization server to direct
+    /// token requests (including client credentials) to an arbitrary host.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn client(&self, config: &HttpClientConfig) -> Result<reqwest::Client, HttpClientError> {
+        if let Some(ref client) = self.provided_client {
+            return Ok(client.clone());
+        }
+
+        let mut cached = self.client.lock().unwrap();
+        if let Some((ref cached_config, ref client)) = *cached {
+            if cached_config == config {
+                return Ok(client.clone());
+            }
+        }
+
+        let mut builder = configure_builder!(reqwest::Client::builder(), config);
+        if let Some(timeout) = config.timeout() {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder.build()?;
+
+        *cached = Some((config.clone(), client.clone()));
+        Ok(client)
+    }
+}
+
+///
+/// Wraps an existing `reqwest::Client`, which is used for all requests as is.
+///
+/// The `HttpClientConfig` of each request (e.g., timeouts, proxy, and TLS settings) is ignored,
+/// so these settings must be configured on the `reqwest::Client` instead. The client should be
+/// configured not to follow redirects (see `AsyncReqwestHttpClient::new`).
+///
+impl From<reqwest::Client> for AsyncReqwestHttpClient {
+    fn from(client: reqwest::Client) -> Self {
+        AsyncReqwestHttpClient {
+            client: Arc::default(),
+            provided_client: Some(client),
+        }
+    }
+}
+
+impl AsyncHttpClient for AsyncReqwestHttpClient {
+    fn request_async(&self, request: HttpRequest) -> HttpClientFuture {
+        let client = self.client(&request.config);
+
+        Box::pin(async move {
+            let response = client?
+                .request(request.method, request.url.as_str())
+                .headers(request.headers)
+                .body(request.body)
+                .send()
+                .await?;
+
+            let status_code = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+
+            Ok(HttpResponse {
+                status_code,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+///
+/// Synchronous HTTP client backed by [reqwest](https://crates.io/crates/reqwest)'s blocking API.
+///
+/// A new `reqwest::blocking::Client` is created for each request. Prefer `ReqwestHttpClient` when
+/// sending many requests.
+///
+pub fn http_client(request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+    ReqwestHttpClient::new().request(request)
+}
+
+///
+/// Asynchronous HTTP client backed by [reqwest](https://crates.io/crates/reqwest).
+///
+/// A new `reqwest::Client` is created for each request. Prefer `AsyncReqwestHttpClient` when
+/// sending many requests.
+///
+pub async fn async_http_client(request: HttpRequest) -> Result<HttpResponse, HttpClientError> {
+    AsyncReqwestHttpClient::new().request_async(request).await
+}
+
+fn proxies(config: &HttpClientConfig) -> Result<Vec<Proxy>, HttpClientError> {
+    let no_proxy = NoProxy::from_string(&config.no_proxy().join(","));
+
+    if let Some(http_proxy) = config.proxy() {
+        let mut proxy = Proxy::all(http_proxy.url().as_str())?.no_proxy(no_proxy);
+        if let Some((username, password)) = http_proxy.credentials() {
+            proxy = proxy.basic_auth(username, password.secret());
+        }
+        return Ok(vec![proxy]);
+    }
+
+    // Without any hosts to exclude, reqwest's default detection of proxies from the environment
+    // applies. Otherwise, the environment proxies are configured explicitly (which disables the
+    // default detection) so that the hosts are excluded just as with the curl HTTP client, which
+    // likewise disregards the `no_proxy` environment variable in this case.
+    if no_proxy.is_none() {
+        return Ok(Vec::new());
+    }
+    let env_proxy = |names: &[&str]| {
+        names
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|url| !url.is_empty())
+    };
+    let mut proxies = Vec::new();
+    if let Some(url) = env_proxy(&["http_proxy", "HTTP_PROXY"]) {
+        proxies.push(Proxy::http(&url)?.no_proxy(no_proxy.clone()));
+    }
+    if let Some(url) = env_proxy(&["https_proxy", "HTTPS_PROXY"]) {
+        proxies.push(Proxy::https(&url)?.no_proxy(no_proxy.clone()));
+    }
+    if let Some(url) = env_proxy(&["all_proxy", "ALL_PROXY"]) {
+        proxies.push(Proxy::all(&url)?.no_proxy(no_proxy));
+    }
+    Ok(proxies)
+}
+
+fn check_tls_support(config: &HttpClientConfig) -> Result<(), HttpClientError> {
+    if !config.pinned_public_keys().is_empty() {
+        return Err(HttpClientError::Other(
+            "Public key pinning is not supported by the reqwest HTTP clients".to_string(),
+        ));
+    }
+    if cfg!(not(any(feature = "native-tls", feature = "rustls-tls")))
+        && (!config.root_certificates().is_empty() || config.client_certificate().is_some())
+    {
+        return Err(HttpClientError::Other(
+            "TLS settings require the `native-tls` or `rustls-tls` feature".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+fn root_certificates(config: &HttpClientConfig) -> Result<Vec<Certificate>, HttpClientError> {
+    let mut certificates = Vec::new();
+    for bundle in config.root_certificates() {
+        certificates.extend(Certificate::from_pem_bundle(bundle)?);
+    }
+    Ok(certificates)
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+fn identity(config: &HttpClientConfig) -> Result<Option<Identity>, HttpClientError> {
+    let (certificate, private_key) = match config.client_certificate() {
+        Some(client_certificate) => client_certificate,
+        None => return Ok(None),
+    };
+
+    // reqwest uses native-tls by default whenever it's enabled, even if rustls is also enabled.
+    #[cfg(feature = "native-tls")]
+    let identity = Identity::from_pkcs8_pem(certificate, private_key.secret())?;
+    #[cfg(not(feature = "native-tls"))]
+    let identity = {
+        let mut pem = certificate.to_vec();
+        pem.push(b'\n');
+        pem.extend_from_slice(private_key.secret());
+        Identity::from_pem(&pem)?
+    };
+
+    Ok(Some(identity))
+}
+
+impl From<reqwest::Error> for HttpClientError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            HttpClientError::Timeout
+        } else {
+            HttpClientError::Transport(err.into())
+        }
+    }
+}
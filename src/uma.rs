@@ -0,0 +1,528 @@
+// `#[derive(Fail)]` places its impls inside a named constant, which this lint flags.
+#![allow(non_local_definitions)]
+
+use std::fmt::Error as FormatterError;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::time::Duration;
+
+use http::status::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use super::basic::BasicErrorResponseType;
+use super::prelude::*;
+use super::{
+    helpers, parse_response, AccessToken, ClaimToken, ClaimTokenFormat, Client, ErrorResponse,
+    ErrorResponseType, ExtraTokenFields, HttpResponse, PermissionTicket, PersistedClaimsToken,
+    PreparedRequest, RequestTokenError, Scope, ScopeField, TokenResponse, TokenType,
+};
+
+const UMA_TICKET_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:uma-ticket";
+
+impl<EF, TT, SF, TE> Client<EF, TT, SF, TE>
+where
+    EF: ExtraTokenFields,
+    TT: TokenType,
+    SF: ScopeField,
+    TE: ErrorResponseType,
+{
+    ///
+    /// Exchanges a permission ticket for a requesting party token (RPT) using the UMA grant.
+    ///
+    /// The request may include a claim token or persisted claims token (PCT) containing claims
+    /// about the requesting party, as well as a previously issued RPT to upgrade. If the
+    /// authorization server requires additional claims or has deferred its decision, this method
+    /// returns `Err(UmaGrantError::NeedInfo(_))` or `Err(UmaGrantError::RequestSubmitted(_))`,
+    /// respectively, each containing the permission ticket to use in subsequent requests.
+    ///
+    /// See https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html#uma-grant-type
+    ///
+    pub fn exchange_uma_ticket(
+        &self,
+        request: &UmaGrantRequest,
+    ) -> Result<UmaTokenResponse<EF, TT, SF>, UmaGrantError> {
+        let uma_request = self.prepare_uma_ticket_request(request)?;
+        let http_response = self.send_http_request::<UmaErrorResponseType>(&uma_request, false)?;
+        parse_uma_response(http_response)
+    }
+
+    ///
+    /// Asynchronous version of `exchange_uma_ticket`, which sends the request using the HTTP
+    /// client configured via `set_async_http_client`.
+    ///
+    pub fn exchange_uma_ticket_async(
+        &self,
+        request: &UmaGrantRequest,
+    ) -> impl Future<Output = Result<UmaTokenResponse<EF, TT, SF>, UmaGrantError>> {
+        let uma_request = self.prepare_uma_ticket_request(request);
+        let sender = self.async_sender(false);
+
+        async move {
+            let http_response = sender
+                .send_http_request::<UmaErrorResponseType>(&uma_request?)
+                .await?;
+            parse_uma_response(http_response)
+        }
+    }
+
+    fn prepare_uma_ticket_request(
+        &self,
+        request: &UmaGrantRequest,
+    ) -> Result<PreparedRequest, RequestTokenError<UmaErrorResponseType>> {
+        // Generate the space-delimited scopes String before initializing params so that it has
+        // a long enough lifetime.
+        let scopes_opt = if request.scopes.is_empty() {
+            None
+        } else {
+            Some(
+                request
+                    .scopes
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        };
+
+        let mut params: Vec<(&str, &str)> = vec![
+            ("grant_type", UMA_TICKET_GRANT_TYPE),
+            ("ticket", &request.ticket),
+        ];
+        if let Some((ref claim_token, ref claim_token_format)) = request.claim_token {
+            params.push(("claim_token", claim_token.secret()));
+            params.push(("claim_token_format", claim_token_format));
+        }
+        if let Some(ref pct) = request.pct {
+            params.push(("pct", pct.secret()));
+        }
+        if let Some(ref rpt) = request.rpt {
+            params.push(("rpt", rpt.secret()));
+        }
+        if let Some(ref scopes) = scopes_opt {
+            params.push(("scope", scopes));
+        }
+
+        self.prepare_token_request(params)
+    }
+}
+
+///
+/// Parameters of a [UMA grant](https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html#uma-grant-type)
+/// request, which exchanges a permission ticket for a requesting party token (RPT).
+///
+#[derive(Clone, Debug)]
+pub struct UmaGrantRequest {
+    ticket: PermissionTicket,
+    claim_token: Option<(ClaimToken, ClaimTokenFormat)>,
+    pct: Option<PersistedClaimsToken>,
+    rpt: Option<AccessToken>,
+    scopes: Vec<Scope>,
+}
+
+impl UmaGrantRequest {
+    ///
+    /// Creates a request for the permissions represented by the given permission ticket.
+    ///
+    pub fn new(ticket: PermissionTicket) -> Self {
+        UmaGrantRequest {
+            ticket,
+            claim_token: None,
+            pct: None,
+            rpt: None,
+            scopes: Vec::new(),
+        }
+    }
+
+    ///
+    /// Pushes a token containing claims about the requesting party to the authorization server.
+    ///
+    pub fn set_claim_token(
+        mut self,
+        claim_token: ClaimToken,
+        claim_token_format: ClaimTokenFormat,
+    ) -> Self {
+        self.claim_token = Some((claim_token, claim_token_format));
+
+        self
+    }
+
+    ///
+    /// Sets the persisted claims token (PCT) returned by a previous UMA grant request.
+    ///
+    pub fn set_pct(mut self, pct: PersistedClaimsToken) -> Self {
+        self.pct = Some(pct);
+
+        self
+    }
+
+    ///
+    /// Sets a previously issued requesting party token (RPT), which the authorization server may
+    /// upgrade by adding the newly granted permissions to it.
+    ///
+    pub fn set_rpt(mut self, rpt: AccessToken) -> Self {
+        self.rpt = Some(rpt);
+
+        self
+    }
+
+    ///
+    /// Appends a scope to the request, in addition to those associated with the permission
+    /// ticket.
+    ///
+    pub fn add_scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+
+        self
+    }
+}
+
+///
+/// Response returned by the token endpoint for a successful UMA grant request, as described in
+/// [Section 3.3.5 of the UMA 2.0 Grant](https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html#give-rpt).
+///
+/// The access token contained in this response is the requesting party token (RPT).
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct UmaTokenResponse<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pct: Option<PersistedClaimsToken>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upgraded: Option<bool>,
+    #[serde(bound = "EF: ExtraTokenFields, TT: TokenType, SF: ScopeField")]
+    #[serde(flatten)]
+    token: TokenResponse<EF, TT, SF>,
+}
+
+impl<EF: ExtraTokenFields, TT: TokenType, SF: ScopeField> UmaTokenResponse<EF, TT, SF> {
+    ///
+    /// OPTIONAL. Persisted claims token (PCT), which may be used in subsequent UMA grant
+    /// requests to avoid gathering the same claims again.
+    ///
+    pub fn pct(&self) -> Option<&PersistedClaimsToken> {
+        self.pct.as_ref()
+    }
+    ///
+    /// OPTIONAL. Indicates whether the RPT provided in the request was upgraded with the newly
+    /// granted permissions. Defaults to `false`.
+    ///
+    pub fn upgraded(&self) -> bool {
+        self.upgraded.unwrap_or(false)
+    }
+    ///
+    /// The token response containing the requesting party token (RPT).
+    ///
+    pub fn token(&self) -> &TokenResponse<EF, TT, SF> {
+        &self.token
+    }
+    ///
+    /// Converts this response into the token response containing the requesting party token
+    /// (RPT).
+    ///
+    pub fn into_token(self) -> TokenResponse<EF, TT, SF> {
+        self.token
+    }
+}
+
+///
+/// UMA grant error types.
+///
+/// These error types are defined in
+/// [Section 3.3.6 of the UMA 2.0 Grant](https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html#authorization-failure),
+/// in addition to the basic error types defined in
+/// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+///
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UmaErrorResponseType {
+    ///
+    /// The authorization server needs additional claims about the requesting party.
+    ///
+    NeedInfo,
+    ///
+    /// The authorization server denied the requested permissions.
+    ///
+    RequestDenied,
+    ///
+    /// The authorization server has not yet reached a decision, and the client should retry the
+    /// request after the polling interval.
+    ///
+    RequestSubmitted,
+    ///
+    /// A basic error type defined in
+    /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+    ///
+    #[serde(untagged)]
+    Basic(BasicErrorResponseType),
+}
+
+impl ErrorResponseType for UmaErrorResponseType {}
+
+impl Debug for UmaErrorResponseType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for UmaErrorResponseType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        match *self {
+            UmaErrorResponseType::Basic(ref basic) => Display::fmt(basic, f),
+            _ => write!(f, "{}", helpers::variant_name(&self)),
+        }
+    }
+}
+
+///
+/// Claim required by the authorization server before it can issue a requesting party token, as
+/// returned in a `need_info` error response.
+///
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RequiredClaim {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    claim_token_format: Vec<ClaimTokenFormat>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claim_type: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    friendly_name: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    issuer: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl RequiredClaim {
+    ///
+    /// OPTIONAL. Formats in which the claim may be provided via a claim token.
+    ///
+    pub fn claim_token_format(&self) -> &[ClaimTokenFormat] {
+        &self.claim_token_format
+    }
+    ///
+    /// OPTIONAL. Expected interpretation of the claim value.
+    ///
+    pub fn claim_type(&self) -> Option<&String> {
+        self.claim_type.as_ref()
+    }
+    ///
+    /// OPTIONAL. Human-readable name of the claim.
+    ///
+    pub fn friendly_name(&self) -> Option<&String> {
+        self.friendly_name.as_ref()
+    }
+    ///
+    /// OPTIONAL. Issuers from which the claim is acceptable.
+    ///
+    pub fn issuer(&self) -> &[String] {
+        &self.issuer
+    }
+    ///
+    /// OPTIONAL. Name of the claim.
+    ///
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+}
+
+///
+/// Details of a `need_info` error response, indicating that the authorization server needs
+/// additional claims about the requesting party.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeedInfo {
+    ticket: PermissionTicket,
+    required_claims: Vec<RequiredClaim>,
+    redirect_user: Option<String>,
+    error_response: ErrorResponse<UmaErrorResponseType>,
+}
+
+impl NeedInfo {
+    ///
+    /// REQUIRED. Permission ticket to use in the next UMA grant request.
+    ///
+    pub fn ticket(&self) -> &PermissionTicket {
+        &self.ticket
+    }
+    ///
+    /// OPTIONAL. Claims that the client may push to the authorization server via a claim token.
+    /// Malformed entries in the server's response are omitted.
+    ///
+    pub fn required_claims(&self) -> &[RequiredClaim] {
+        &self.required_claims
+    }
+    ///
+    /// OPTIONAL. Claims interaction endpoint URI to which the client may redirect the requesting
+    /// party for interactive claims gathering.
+    ///
+    pub fn redirect_user(&self) -> Option<&String> {
+        self.redirect_user.as_ref()
+    }
+    ///
+    /// The error response returned by the authorization server.
+    ///
+    pub fn error_response(&self) -> &ErrorResponse<UmaErrorResponseType> {
+        &self.error_response
+    }
+}
+
+impl Display for NeedInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        Display::fmt(&self.error_response, f)
+    }
+}
+
+///
+/// Details of a `request_submitted` error response, indicating that the authorization server
+/// has not yet reached a decision on the requested permissions.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestSubmitted {
+    ticket: PermissionTicket,
+    interval: u64,
+    error_response: ErrorResponse<UmaErrorResponseType>,
+}
+
+impl RequestSubmitted {
+    ///
+    /// REQUIRED. Permission ticket to use in the next UMA grant request.
+    ///
+    pub fn ticket(&self) -> &PermissionTicket {
+        &self.ticket
+    }
+    ///
+    /// OPTIONAL. The minimum amount of time that the client should wait before retrying the
+    /// request. Defaults to 5 seconds.
+    ///
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+    ///
+    /// The error response returned by the authorization server.
+    ///
+    pub fn error_response(&self) -> &ErrorResponse<UmaErrorResponseType> {
+        &self.error_response
+    }
+}
+
+impl Display for RequestSubmitted {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        Display::fmt(&self.error_response, f)
+    }
+}
+
+///
+/// Error encountered during a UMA grant request.
+///
+#[derive(Debug, Fail)]
+pub enum UmaGrantError {
+    ///
+    /// The authorization server needs additional claims about the requesting party
+    /// (`need_info`).
+    ///
+    #[fail(display = "Server returned error response `{}`", _0)]
+    NeedInfo(Box<NeedInfo>),
+    ///
+    /// The authorization server has not yet reached a decision (`request_submitted`).
+    ///
+    #[fail(display = "Server returned error response `{}`", _0)]
+    RequestSubmitted(Box<RequestSubmitted>),
+    ///
+    /// Any other error, including `request_denied` error responses.
+    ///
+    #[fail(display = "{}", _0)]
+    Token(#[cause] RequestTokenError<UmaErrorResponseType>),
+}
+
+impl From<RequestTokenError<UmaErrorResponseType>> for UmaGrantError {
+    fn from(err: RequestTokenError<UmaErrorResponseType>) -> Self {
+        UmaGrantError::Token(err)
+    }
+}
+
+// Extension fields of UMA error responses (see
+// https://docs.kantarainitiative.org/uma/wg/rec-oauth-uma-grant-2.0.html#authorization-failure).
+#[derive(Deserialize)]
+struct UmaErrorFields {
+    ticket: Option<PermissionTicket>,
+    #[serde(default, deserialize_with = "deserialize_required_claims")]
+    required_claims: Vec<RequiredClaim>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    redirect_user: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    interval: Option<u64>,
+}
+
+// Skips malformed required claims so that a single unexpected entry doesn't prevent the client
+// from receiving the permission ticket needed to continue.
+fn deserialize_required_claims<'de, D>(deserializer: D) -> Result<Vec<RequiredClaim>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(claims) => claims
+            .into_iter()
+            .filter_map(|claim| serde_json::from_value(claim).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+// Ignores malformed optional fields for the same reason as `deserialize_required_claims`.
+fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+fn parse_uma_response<EF, TT, SF>(
+    http_response: HttpResponse,
+) -> Result<UmaTokenResponse<EF, TT, SF>, UmaGrantError>
+where
+    EF: ExtraTokenFields,
+    TT: TokenType,
+    SF: ScopeField,
+{
+    let fields = if http_response.status_code != StatusCode::OK {
+        serde_json::from_slice::<UmaErrorFields>(&http_response.body).ok()
+    } else {
+        None
+    };
+
+    match (parse_response(http_response), fields) {
+        (
+            Err(RequestTokenError::ServerResponse(error_response)),
+            Some(UmaErrorFields {
+                ticket: Some(ticket),
+                required_claims,
+                redirect_user,
+                interval,
+            }),
+        ) => match *error_response.error() {
+            UmaErrorResponseType::NeedInfo => Err(UmaGrantError::NeedInfo(Box::new(NeedInfo {
+                ticket,
+                required_claims,
+                redirect_user,
+                error_response,
+            }))),
+            UmaErrorResponseType::RequestSubmitted => Err(UmaGrantError::RequestSubmitted(
+                Box::new(RequestSubmitted {
+                    ticket,
+                    interval: interval.unwrap_or_else(super::default_polling_interval),
+                    error_response,
+                }),
+            )),
+            _ => Err(RequestTokenError::ServerResponse(error_response).into()),
+        },
+        (result, _) => Ok(result?),
+    }
+}
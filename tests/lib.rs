@@ -1,17 +1,64 @@
+extern crate base64;
+#[cfg(feature = "jwt")]
+extern crate ed25519_dalek;
+extern crate futures;
+extern crate hmac;
+extern crate http;
 extern crate mockito;
 extern crate oauth2;
+#[cfg(feature = "jwt")]
+extern crate p256;
+#[cfg(feature = "reqwest")]
+extern crate reqwest;
+#[cfg(feature = "jwt")]
+extern crate rsa;
 extern crate serde;
 extern crate url;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
-
+extern crate sha2;
+
+use futures::executor::block_on;
+use futures::future;
+use hmac::{Hmac, Mac};
+use http::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use http::method::Method;
+use http::status::StatusCode;
+#[cfg(any(feature = "curl", feature = "reqwest"))]
 use mockito::{mock, server_url};
-use url::form_urlencoded::byte_serialize;
+#[cfg(feature = "jwt")]
+use rsa::pkcs8::DecodePrivateKey;
+#[cfg(feature = "jwt")]
+use rsa::signature::Verifier;
+#[cfg(feature = "jwt")]
+use rsa::RsaPrivateKey;
+#[cfg(feature = "jwt")]
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::HashSet;
+#[cfg(feature = "jwt")]
+use std::convert::TryFrom;
+#[cfg(any(feature = "curl", feature = "reqwest"))]
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+use url::form_urlencoded::{self, byte_serialize};
 use url::Url;
 
+use oauth2::auth::*;
 use oauth2::basic::*;
+use oauth2::cassette::{RecordingHttpClient, ReplayHttpClient};
+use oauth2::dpop::jwk_thumbprint;
+#[cfg(feature = "jwt")]
+use oauth2::dpop::{DpopKey, DPOP, DPOP_NONCE};
+use oauth2::jwt::JwtSigningError;
+use oauth2::jwt::HmacSigningKey;
+#[cfg(feature = "jwt")]
+use oauth2::jwt::{EcSigningKey, Ed25519SigningKey, RsaSigningKey};
 use oauth2::prelude::*;
+use oauth2::uma::*;
 use oauth2::*;
 
 fn new_client() -> BasicClient {
@@ -25,6 +72,7 @@ fn new_client() -> BasicClient {
     )
 }
 
+#[cfg(any(feature = "curl", feature = "reqwest"))]
 fn new_mock_client() -> BasicClient {
     BasicClient::new(
         ClientId::new("aaa".to_string()),
@@ -36,6 +84,7 @@ fn new_mock_client() -> BasicClient {
     )
 }
 
+#[cfg(any(feature = "curl", feature = "reqwest"))]
 fn new_mock_client_with_unsafe_chars() -> BasicClient {
     BasicClient::new(
         ClientId::new("aaa/;&".to_string()),
@@ -211,6 +260,70 @@ fn test_authorize_url_with_scopes() {
     );
 }
 
+#[test]
+fn test_authorize_url_with_resources() {
+    let client = new_client()
+        .add_scope(Scope::new("read".to_string()))
+        .add_resource(ResourceUrl::new(
+            Url::parse("https://api.example.com/").unwrap(),
+        ))
+        .add_resource(ResourceUrl::new(
+            Url::parse("https://ledger.example.com/").unwrap(),
+        ));
+
+    let (url, _) = client.authorize_url(|| CsrfToken::new("csrf_token".to_string()));
+
+    assert_eq!(
+        Url::parse(
+            "http://example.com/auth?response_type=code&client_id=aaa&scope=read\
+             &resource=https%3A%2F%2Fapi.example.com%2F\
+             &resource=https%3A%2F%2Fledger.example.com%2F&state=csrf_token"
+        )
+        .unwrap(),
+        url
+    );
+
+    // Explicit resource parameters replace those configured on the client.
+    let (url, _) = client.authorize_url_extension(
+        &ResponseType::new("code".to_string()),
+        || CsrfToken::new("csrf_token".to_string()),
+        &[("resource", "https://other.example.com/")],
+    );
+
+    assert_eq!(
+        Url::parse(
+            "http://example.com/auth?response_type=code&client_id=aaa&scope=read\
+             &state=csrf_token&resource=https%3A%2F%2Fother.example.com%2F"
+        )
+        .unwrap(),
+        url
+    );
+}
+
+#[test]
+fn test_authorize_url_with_authorization_details() {
+    let client = new_client().add_authorization_detail(
+        AuthorizationDetail::new(AuthorizationDetailType::new(
+            "account_information".to_string(),
+        ))
+        .add_action("list_accounts".to_string())
+        .add_location("https://example.com/accounts".to_string()),
+    );
+
+    let (url, _) = client.authorize_url(|| CsrfToken::new("csrf_token".to_string()));
+
+    assert_eq!(
+        Some(
+            "[{\"type\":\"account_information\",\"locations\":[\"https://example.com/accounts\"],\
+             \"actions\":[\"list_accounts\"]}]"
+                .to_string()
+        ),
+        url.query_pairs()
+            .find(|(name, _)| name == "authorization_details")
+            .map(|(_, value)| value.into_owned())
+    );
+}
+
 #[test]
 fn test_authorize_url_with_extension_response_type() {
     let client = new_client();
@@ -249,6 +362,7 @@ fn test_authorize_url_with_redirect_url() {
     );
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_successful_with_minimal_json_response() {
     let mock = mock("POST", "/token")
@@ -290,6 +404,7 @@ fn test_exchange_code_successful_with_minimal_json_response() {
     assert_eq!(token, deserialized_token);
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_successful_with_complete_json_response() {
     let mock = mock("POST", "/token")
@@ -334,6 +449,7 @@ fn test_exchange_code_successful_with_complete_json_response() {
     assert_eq!(token, deserialized_token);
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_client_credentials_with_basic_auth() {
     let mock = mock("POST", "/token")
@@ -366,6 +482,7 @@ fn test_exchange_client_credentials_with_basic_auth() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_client_credentials_with_body_auth_and_scope() {
     let mock = mock("POST", "/token")
@@ -401,6 +518,7 @@ fn test_exchange_client_credentials_with_body_auth_and_scope() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_refresh_token_with_basic_auth() {
     let mock = mock("POST", "/token")
@@ -432,6 +550,7 @@ fn test_exchange_refresh_token_with_basic_auth() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_refresh_token_with_json_response() {
     let mock = mock("POST", "/token")
@@ -465,6 +584,7 @@ fn test_exchange_refresh_token_with_json_response() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_password_with_json_response() {
     let mock = mock("POST", "/token")
@@ -502,6 +622,7 @@ fn test_exchange_password_with_json_response() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_successful_with_redirect_url() {
     let mock = mock("POST", "/token")
@@ -540,6 +661,7 @@ fn test_exchange_code_successful_with_redirect_url() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_successful_with_basic_auth() {
     let mock = mock("POST", "/token")
@@ -578,6 +700,7 @@ fn test_exchange_code_successful_with_basic_auth() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_successful_with_extension() {
     let mock = mock("POST", "/token")
@@ -623,6 +746,7 @@ fn test_exchange_code_successful_with_extension() {
     assert_eq!(None, token.refresh_token());
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_with_simple_json_error() {
     let mock = mock("POST", "/token")
@@ -699,6 +823,7 @@ fn test_exchange_code_with_simple_json_error() {
     );
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_with_json_parse_error() {
     let mock = mock("POST", "/token")
@@ -726,6 +851,7 @@ fn test_exchange_code_with_json_parse_error() {
     }
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_with_unexpected_content_type() {
     let mock = mock("POST", "/token")
@@ -754,6 +880,7 @@ fn test_exchange_code_with_unexpected_content_type() {
     }
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_with_invalid_token_type() {
     let mock = mock("POST", "/token")
@@ -789,6 +916,7 @@ fn test_exchange_code_with_invalid_token_type() {
     }
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_with_400_status_code() {
     let body = r#"{"error":"invalid_request","error_description":"Expired code."}"#;
@@ -824,6 +952,7 @@ fn test_exchange_code_with_400_status_code() {
     }
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_exchange_code_fails_gracefully_on_transport_error() {
     let client = BasicClient::new(
@@ -844,6 +973,3119 @@ fn test_exchange_code_fails_gracefully_on_transport_error() {
     }
 }
 
+#[test]
+fn test_exchange_client_credentials_with_custom_http_client() {
+    let client = new_client()
+        .add_scope(Scope::new("read".to_string()))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(Url::parse("http://example.com/token").unwrap(), request.url);
+            assert_eq!(Method::POST, request.method);
+            assert_eq!("application/json", request.headers[ACCEPT]);
+            assert_eq!(
+                "application/x-www-form-urlencoded",
+                request.headers[CONTENT_TYPE]
+            );
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!(
+                "grant_type=client_credentials&scope=read",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Ok(HttpResponse {
+                status_code: StatusCode::OK,
+                headers,
+                body: b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}".to_vec(),
+            })
+        });
+
+    let token = client.exchange_client_credentials().unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+    assert_eq!(None, token.scopes());
+}
+
+#[test]
+fn test_exchange_code_with_custom_http_client_error() {
+    let client = new_client().set_http_client(|_: HttpRequest| {
+        Err(HttpClientError::Other("connection refused".to_string()))
+    });
+
+    let token = client.exchange_code(AuthorizationCode::new("ccc".to_string()));
+
+    match token.err().unwrap() {
+        RequestTokenError::Request(HttpClientError::Other(error_str)) => {
+            assert_eq!("connection refused", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_code_async_successful() {
+    let client = new_client().set_async_http_client(|request: HttpRequest| {
+        assert_eq!(Url::parse("http://example.com/token").unwrap(), request.url);
+        // base64("aaa:bbb")
+        assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+        assert_eq!(
+            "grant_type=authorization_code&code=ccc",
+            String::from_utf8(request.body).unwrap()
+        );
+
+        async {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Ok(HttpResponse {
+                status_code: StatusCode::OK,
+                headers,
+                body: b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \
+                        \"expires_in\": 3600, \"refresh_token\": \"foobar\"}"
+                    .to_vec(),
+            })
+        }
+    });
+
+    let token =
+        block_on(client.exchange_code_async(AuthorizationCode::new("ccc".to_string()))).unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+    assert_eq!(3600, token.expires_in().unwrap().as_secs());
+    assert_eq!("foobar", token.refresh_token().unwrap().secret());
+}
+
+#[test]
+fn test_exchange_password_async_with_json_error() {
+    let client = new_client()
+        .set_auth_type(oauth2::AuthType::RequestBody)
+        .set_async_http_client(|request: HttpRequest| {
+            assert_eq!(
+                "grant_type=password&username=user&password=pass&client_id=aaa&client_secret=bbb",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            future::ready(Ok(HttpResponse {
+                status_code: StatusCode::BAD_REQUEST,
+                headers,
+                body: b"{\"error\": \"invalid_grant\"}".to_vec(),
+            }))
+        });
+
+    let token = block_on(client.exchange_password_async(
+        &ResourceOwnerUsername::new("user".to_string()),
+        &ResourceOwnerPassword::new("pass".to_string()),
+    ));
+
+    match token.err().unwrap() {
+        RequestTokenError::ServerResponse(error_response) => {
+            assert_eq!(
+                BasicErrorResponseType::InvalidGrant,
+                *error_response.error()
+            );
+            assert_eq!(None, error_response.error_description());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_refresh_token_async_without_async_http_client() {
+    let client = new_client();
+
+    let token =
+        block_on(client.exchange_refresh_token_async(&RefreshToken::new("ccc".to_string())));
+
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("async_http_client must be set", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+fn json_response(status_code: StatusCode, body: &str) -> HttpResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    HttpResponse {
+        status_code,
+        headers,
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+fn device_details(expires_in: u64, interval: u64) -> DeviceAuthorizationResponse {
+    serde_json::from_str(&format!(
+        "{{\"device_code\": \"dcdc\", \"user_code\": \"ABCD-EFGH\", \
+         \"verification_uri\": \"http://example.com/device\", \"expires_in\": {}, \
+         \"interval\": {}}}",
+        expires_in, interval
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_exchange_device_code() {
+    let client = new_client()
+        .set_device_authorization_url(DeviceAuthorizationUrl::new(
+            Url::parse("http://example.com/device_authorization").unwrap(),
+        ))
+        .add_scope(Scope::new("read".to_string()))
+        .set_redirect_url(RedirectUrl::new(
+            Url::parse("http://example.com/redirect").unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(
+                Url::parse("http://example.com/device_authorization").unwrap(),
+                request.url
+            );
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!("scope=read", String::from_utf8(request.body).unwrap());
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"device_code\": \"dcdc\", \"user_code\": \"ABCD-EFGH\", \
+                 \"verification_url\": \"http://example.com/device\", \"expires_in\": 1800}",
+            ))
+        });
+
+    let details = client.exchange_device_code().unwrap();
+
+    assert_eq!("dcdc", details.device_code().secret());
+    assert_eq!("ABCD-EFGH", details.user_code().secret());
+    assert_eq!(
+        Url::parse("http://example.com/device").unwrap(),
+        **details.verification_uri()
+    );
+    assert_eq!(None, details.verification_uri_complete());
+    assert_eq!(Duration::from_secs(1800), details.expires_in());
+    assert_eq!(Duration::from_secs(5), details.interval());
+}
+
+#[test]
+fn test_exchange_code_with_no_client_authentication() {
+    let client = new_client()
+        .set_client_authentication(NoAuthentication)
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(
+                "grant_type=authorization_code&code=ccc&client_id=aaa",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let token = client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_device_code_with_endpoint_client_authentication() {
+    let client = new_client()
+        .set_device_authorization_url(DeviceAuthorizationUrl::new(
+            Url::parse("http://example.com/device_authorization").unwrap(),
+        ))
+        .set_endpoint_client_authentication(Endpoint::DeviceAuthorization, ClientSecretPost)
+        .set_http_client(|request: HttpRequest| {
+            if request.url.path() == "/device_authorization" {
+                assert_eq!(None, request.headers.get(AUTHORIZATION));
+                assert_eq!(
+                    "client_id=aaa&client_secret=bbb",
+                    String::from_utf8(request.body).unwrap()
+                );
+
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"device_code\": \"dcdc\", \"user_code\": \"ABCD-EFGH\", \
+                     \"verification_uri\": \"http://example.com/device\", \"expires_in\": 60, \
+                     \"interval\": 0}",
+                ))
+            } else {
+                // base64("aaa:bbb")
+                assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+                ))
+            }
+        });
+
+    let details = client.exchange_device_code().unwrap();
+    let token = client.exchange_device_access_token(&details).unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_client_credentials_with_custom_client_authentication() {
+    struct ApiKeyAuthentication(Option<&'static str>);
+
+    impl ClientAuthentication for ApiKeyAuthentication {
+        fn authenticate(
+            &self,
+            context: &ClientAuthenticationContext,
+            params: &mut Vec<(String, String)>,
+            headers: &mut HeaderMap,
+        ) -> Result<(), ClientAuthenticationError> {
+            let api_key = self
+                .0
+                .ok_or_else(|| ClientAuthenticationError::Other("missing API key".to_string()))?;
+            assert_eq!(Endpoint::Token, context.endpoint());
+            assert_eq!("http://example.com/token", context.url().as_str());
+
+            params.push(("client_id".to_string(), context.client_id().to_string()));
+            headers.insert("x-api-key", HeaderValue::from_static(api_key));
+            Ok(())
+        }
+    }
+
+    let client = new_client()
+        .set_client_authentication(ApiKeyAuthentication(Some("kkkk")))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!("kkkk", request.headers["x-api-key"]);
+            assert_eq!(
+                "grant_type=client_credentials&client_id=aaa",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+    let token = client.exchange_client_credentials().unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    let token = client
+        .set_client_authentication(ApiKeyAuthentication(None))
+        .exchange_client_credentials();
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Client authentication failed: missing API key", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_device_code_without_device_authorization_url() {
+    let token = new_client().exchange_device_code();
+
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("device_authorization_url must not be `None`", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_device_access_token_after_pending() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client().set_http_client(move |request: HttpRequest| {
+        assert_eq!(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code&device_code=dcdc",
+            String::from_utf8(request.body).unwrap()
+        );
+        if attempts_clone.fetch_add(1, Ordering::SeqCst) < 2 {
+            Ok(json_response(
+                StatusCode::BAD_REQUEST,
+                "{\"error\": \"authorization_pending\"}",
+            ))
+        } else {
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        }
+    });
+
+    let token = client
+        .exchange_device_access_token(&device_details(60, 0))
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(3, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_device_access_token_denied() {
+    let client = new_client().set_http_client(|_: HttpRequest| {
+        Ok(json_response(
+            StatusCode::BAD_REQUEST,
+            "{\"error\": \"access_denied\"}",
+        ))
+    });
+
+    let token = client.exchange_device_access_token(&device_details(60, 0));
+
+    match token.err().unwrap() {
+        RequestTokenError::ServerResponse(error) => {
+            assert_eq!(DeviceCodeErrorResponseType::AccessDenied, *error.error());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_polling_actions() {
+    assert_eq!(
+        PollingAction::Continue,
+        DeviceCodeErrorResponseType::AuthorizationPending.polling_action()
+    );
+    assert_eq!(
+        PollingAction::SlowDown,
+        DeviceCodeErrorResponseType::SlowDown.polling_action()
+    );
+    assert_eq!(
+        PollingAction::Stop,
+        DeviceCodeErrorResponseType::Basic(BasicErrorResponseType::InvalidGrant).polling_action()
+    );
+    assert_eq!(
+        PollingAction::Continue,
+        CibaErrorResponseType::AuthorizationPending.polling_action()
+    );
+    assert_eq!(
+        PollingAction::SlowDown,
+        CibaErrorResponseType::SlowDown.polling_action()
+    );
+    assert_eq!(
+        PollingAction::Stop,
+        CibaErrorResponseType::TransactionFailed.polling_action()
+    );
+    // Error types that don't override `polling_action` stop polling.
+    assert_eq!(
+        PollingAction::Stop,
+        BasicErrorResponseType::InvalidGrant.polling_action()
+    );
+}
+
+#[test]
+fn test_exchange_device_access_token_async_slow_down_until_expired() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client().set_async_http_client(move |_: HttpRequest| {
+        attempts_clone.fetch_add(1, Ordering::SeqCst);
+        future::ready(Ok(json_response(
+            StatusCode::BAD_REQUEST,
+            "{\"error\": \"slow_down\"}",
+        )))
+    });
+
+    // The polling interval increases to 5 seconds, which exceeds the device code's lifetime.
+    let token = block_on(client.exchange_device_access_token_async(&device_details(1, 0)));
+
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!(
+                "Device code expired before authorization was completed",
+                error_str
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert_eq!(1, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_device_access_token_with_huge_lifetime_and_interval() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client().set_http_client(move |_: HttpRequest| {
+        if attempts_clone.fetch_add(1, Ordering::SeqCst) < 1 {
+            Ok(json_response(
+                StatusCode::BAD_REQUEST,
+                "{\"error\": \"authorization_pending\"}",
+            ))
+        } else {
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        }
+    });
+
+    // A device code lifetime too large to represent is treated as having no deadline.
+    let token = client
+        .exchange_device_access_token(&device_details(u64::MAX, 0))
+        .unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+
+    // A polling interval too large to represent fails before polling.
+    let token = client.exchange_device_access_token(&device_details(u64::MAX, u64::MAX));
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Server returned an unsupported polling interval", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    let token = block_on(client.exchange_device_access_token_async(&device_details(60, u64::MAX)));
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Server returned an unsupported polling interval", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_device_code_error_response_type() {
+    let error: DeviceCodeErrorResponseType = serde_json::from_str("\"expired_token\"").unwrap();
+    assert_eq!(DeviceCodeErrorResponseType::ExpiredToken, error);
+    assert_eq!("expired_token", error.to_string());
+
+    let error: DeviceCodeErrorResponseType = serde_json::from_str("\"invalid_grant\"").unwrap();
+    assert_eq!(
+        DeviceCodeErrorResponseType::Basic(BasicErrorResponseType::InvalidGrant),
+        error
+    );
+    assert_eq!("invalid_grant", error.to_string());
+    assert_eq!("\"invalid_grant\"", serde_json::to_string(&error).unwrap());
+}
+
+#[test]
+fn test_exchange_backchannel_authentication() {
+    let client = new_client()
+        .set_backchannel_authentication_url(BackchannelAuthenticationUrl::new(
+            Url::parse("http://example.com/bc-authorize").unwrap(),
+        ))
+        .add_scope(Scope::new("ignored".to_string()))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(
+                Url::parse("http://example.com/bc-authorize").unwrap(),
+                request.url
+            );
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!(
+                "scope=openid+read&login_hint=user%40example.com&binding_message=W4SCT",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"auth_req_id\": \"arid\", \"expires_in\": 120}",
+            ))
+        });
+
+    let request =
+        BackchannelAuthenticationRequest::new(LoginHint::new("user@example.com".to_string()))
+            .set_binding_message(BindingMessage::new("W4SCT".to_string()))
+            .add_scope(Scope::new("openid".to_string()))
+            .add_scope(Scope::new("read".to_string()));
+    let details = client
+        .exchange_backchannel_authentication(&request)
+        .unwrap();
+
+    assert_eq!("arid", details.auth_req_id().secret());
+    assert_eq!(Duration::from_secs(120), details.expires_in());
+    assert_eq!(Duration::from_secs(5), details.interval());
+}
+
+#[test]
+fn test_exchange_backchannel_access_token_after_pending() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client().set_http_client(move |request: HttpRequest| {
+        assert_eq!(
+            "grant_type=urn%3Aopenid%3Aparams%3Agrant-type%3Aciba&auth_req_id=arid",
+            String::from_utf8(request.body).unwrap()
+        );
+        if attempts_clone.fetch_add(1, Ordering::SeqCst) < 2 {
+            Ok(json_response(
+                StatusCode::BAD_REQUEST,
+                "{\"error\": \"authorization_pending\"}",
+            ))
+        } else {
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        }
+    });
+
+    let details =
+        serde_json::from_str("{\"auth_req_id\": \"arid\", \"expires_in\": 60, \"interval\": 0}")
+            .unwrap();
+    let token = client.exchange_backchannel_access_token(&details).unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(3, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_backchannel_access_token_async_transaction_failed() {
+    let client = new_client().set_async_http_client(|_: HttpRequest| {
+        future::ready(Ok(json_response(
+            StatusCode::BAD_REQUEST,
+            "{\"error\": \"transaction_failed\"}",
+        )))
+    });
+
+    let details =
+        serde_json::from_str("{\"auth_req_id\": \"arid\", \"expires_in\": 60, \"interval\": 0}")
+            .unwrap();
+    let token = block_on(client.exchange_backchannel_access_token_async(&details));
+
+    match token.err().unwrap() {
+        RequestTokenError::ServerResponse(error) => {
+            assert_eq!(CibaErrorResponseType::TransactionFailed, *error.error());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_backchannel_access_token_with_huge_lifetime_and_interval() {
+    let client = new_client()
+        .set_http_client(|_: HttpRequest| {
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        })
+        .set_async_http_client(|_: HttpRequest| {
+            future::ready(Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            )))
+        });
+
+    // An authentication request lifetime too large to represent is treated as having no
+    // deadline.
+    let details: BackchannelAuthenticationResponse = serde_json::from_str(
+        "{\"auth_req_id\": \"arid\", \"expires_in\": 18446744073709551615, \"interval\": 0}",
+    )
+    .unwrap();
+    let token = client.exchange_backchannel_access_token(&details).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+    let token = block_on(client.exchange_backchannel_access_token_async(&details)).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    // A polling interval too large to represent fails before polling.
+    let details: BackchannelAuthenticationResponse = serde_json::from_str(
+        "{\"auth_req_id\": \"arid\", \"expires_in\": 60, \"interval\": 18446744073709551615}",
+    )
+    .unwrap();
+    match client.exchange_backchannel_access_token(&details).err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Server returned an unsupported polling interval", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    match block_on(client.exchange_backchannel_access_token_async(&details))
+        .err()
+        .unwrap()
+    {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Server returned an unsupported polling interval", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_uma_ticket_with_rpt_upgrade() {
+    let client = new_client().set_http_client(|request: HttpRequest| {
+        assert_eq!(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Auma-ticket&ticket=tttt\
+             &claim_token=cccc&claim_token_format=\
+             http%3A%2F%2Fopenid.net%2Fspecs%2Fopenid-connect-core-1_0.html%23IDToken\
+             &pct=pppp&rpt=rrrr&scope=read",
+            String::from_utf8(request.body).unwrap()
+        );
+
+        Ok(json_response(
+            StatusCode::OK,
+            "{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \"pct\": \"qqqq\", \
+             \"upgraded\": true}",
+        ))
+    });
+
+    let request = UmaGrantRequest::new(PermissionTicket::new("tttt".to_string()))
+        .set_claim_token(
+            ClaimToken::new("cccc".to_string()),
+            ClaimTokenFormat::id_token(),
+        )
+        .set_pct(PersistedClaimsToken::new("pppp".to_string()))
+        .set_rpt(AccessToken::new("rrrr".to_string()))
+        .add_scope(Scope::new("read".to_string()));
+    let response = client.exchange_uma_ticket(&request).unwrap();
+
+    assert_eq!("12/34", response.token().access_token().secret());
+    assert_eq!("qqqq", response.pct().unwrap().secret());
+    assert!(response.upgraded());
+}
+
+#[test]
+fn test_exchange_uma_ticket_need_info() {
+    let client = new_client().set_http_client(|_: HttpRequest| {
+        Ok(json_response(
+            StatusCode::FORBIDDEN,
+            "{\"error\": \"need_info\", \"ticket\": \"uuuu\", \
+             \"redirect_user\": \"https://as.example.com/rqp_claims?id=2346576421\", \
+             \"required_claims\": [{\"claim_token_format\": \
+             [\"http://openid.net/specs/openid-connect-core-1_0.html#IDToken\"], \
+             \"claim_type\": \"urn:oid:0.9.2342.19200300.100.1.3\", \"friendly_name\": \"email\", \
+             \"issuer\": [\"https://example.com/idp\"], \"name\": \"email23423453ou453\"}]}",
+        ))
+    });
+
+    let result = client.exchange_uma_ticket(&UmaGrantRequest::new(PermissionTicket::new(
+        "tttt".to_string(),
+    )));
+
+    match result.err().unwrap() {
+        UmaGrantError::NeedInfo(need_info) => {
+            assert_eq!("uuuu", need_info.ticket().as_str());
+            assert_eq!(
+                Some(&"https://as.example.com/rqp_claims?id=2346576421".to_string()),
+                need_info.redirect_user()
+            );
+            assert_eq!(1, need_info.required_claims().len());
+            let claim = &need_info.required_claims()[0];
+            assert_eq!(&[ClaimTokenFormat::id_token()], claim.claim_token_format());
+            assert_eq!(Some(&"email".to_string()), claim.friendly_name());
+            assert_eq!(&["https://example.com/idp".to_string()], claim.issuer());
+            assert_eq!(
+                UmaErrorResponseType::NeedInfo,
+                *need_info.error_response().error()
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_uma_ticket_need_info_with_malformed_required_claim() {
+    let client = new_client().set_http_client(|_: HttpRequest| {
+        Ok(json_response(
+            StatusCode::FORBIDDEN,
+            "{\"error\": \"need_info\", \"ticket\": \"uuuu\", \
+             \"required_claims\": [{\"claim_token_format\": \"not-an-array\"}, \
+             {\"friendly_name\": \"email\"}]}",
+        ))
+    });
+
+    let result = client.exchange_uma_ticket(&UmaGrantRequest::new(PermissionTicket::new(
+        "tttt".to_string(),
+    )));
+
+    match result.err().unwrap() {
+        UmaGrantError::NeedInfo(need_info) => {
+            assert_eq!("uuuu", need_info.ticket().as_str());
+            assert_eq!(1, need_info.required_claims().len());
+            assert_eq!(
+                Some(&"email".to_string()),
+                need_info.required_claims()[0].friendly_name()
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_uma_ticket_request_submitted_with_malformed_interval() {
+    let client = new_client().set_http_client(|_: HttpRequest| {
+        Ok(json_response(
+            StatusCode::FORBIDDEN,
+            "{\"error\": \"request_submitted\", \"ticket\": \"uuuu\", \"interval\": \"soon\", \
+             \"redirect_user\": false}",
+        ))
+    });
+
+    let result = client.exchange_uma_ticket(&UmaGrantRequest::new(PermissionTicket::new(
+        "tttt".to_string(),
+    )));
+
+    match result.err().unwrap() {
+        UmaGrantError::RequestSubmitted(request_submitted) => {
+            assert_eq!("uuuu", request_submitted.ticket().as_str());
+            assert_eq!(Duration::from_secs(5), request_submitted.interval());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_uma_ticket_with_dpop_nonce_and_client_secret_jwt() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let requests_clone = requests.clone();
+    let client = new_client()
+        .set_client_authentication(ClientSecretJwt::new())
+        .set_dpop_key(
+            DpopKey::new(EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap()).unwrap(),
+        )
+        .set_http_client(move |request: HttpRequest| {
+            let mut requests = requests_clone.lock().unwrap();
+            requests.push(request);
+
+            if requests.len() == 1 {
+                let mut headers = HeaderMap::new();
+                headers.insert(DPOP_NONCE, HeaderValue::from_static("n1"));
+                Ok(HttpResponse {
+                    status_code: StatusCode::BAD_REQUEST,
+                    headers,
+                    body: b"{\"error\": \"use_dpop_nonce\"}".to_vec(),
+                })
+            } else {
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"DPoP\"}",
+                ))
+            }
+        });
+
+    let token = client
+        .exchange_uma_ticket(&UmaGrantRequest::new(PermissionTicket::new(
+            "tttt".to_string(),
+        )))
+        .unwrap();
+
+    assert_eq!("12/34", token.token().access_token().secret());
+    // The request is resent with a newly signed client assertion and proof.
+    let requests = requests.lock().unwrap();
+    assert_eq!(2, requests.len());
+    let assertions = requests
+        .iter()
+        .map(|request| form_param(&request.body, "client_assertion").unwrap())
+        .collect::<Vec<_>>();
+    assert_ne!(
+        decode_jwt(&assertions[0]).1["jti"],
+        decode_jwt(&assertions[1]).1["jti"]
+    );
+    let proofs = requests
+        .iter()
+        .map(|request| decode_jwt(request.headers[DPOP].to_str().unwrap()).1)
+        .collect::<Vec<_>>();
+    assert_ne!(proofs[0]["jti"], proofs[1]["jti"]);
+    assert_eq!("n1", proofs[1]["nonce"]);
+}
+
+#[test]
+fn test_exchange_uma_ticket_async_request_submitted_and_denied() {
+    let client = new_client().set_async_http_client(|request: HttpRequest| {
+        let body = String::from_utf8(request.body).unwrap();
+        let response = if body.contains("ticket=tttt") {
+            json_response(
+                StatusCode::FORBIDDEN,
+                "{\"error\": \"request_submitted\", \"ticket\": \"uuuu\"}",
+            )
+        } else {
+            json_response(StatusCode::FORBIDDEN, "{\"error\": \"request_denied\"}")
+        };
+        future::ready(Ok(response))
+    });
+
+    let result = block_on(client.exchange_uma_ticket_async(&UmaGrantRequest::new(
+        PermissionTicket::new("tttt".to_string()),
+    )));
+    let ticket = match result.err().unwrap() {
+        UmaGrantError::RequestSubmitted(request_submitted) => {
+            assert_eq!(Duration::from_secs(5), request_submitted.interval());
+            request_submitted.ticket().clone()
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    };
+
+    let result = block_on(client.exchange_uma_ticket_async(&UmaGrantRequest::new(ticket)));
+    match result.err().unwrap() {
+        UmaGrantError::Token(RequestTokenError::ServerResponse(error)) => {
+            assert_eq!(UmaErrorResponseType::RequestDenied, *error.error());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_refresh_token_with_resources() {
+    let client = new_client()
+        .add_resource(ResourceUrl::new(
+            Url::parse("https://api.example.com/").unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            let body = String::from_utf8(request.body).unwrap();
+            let access_token = if body.contains("ledger") {
+                assert_eq!(
+                    "grant_type=refresh_token&refresh_token=ccc\
+                     &resource=https%3A%2F%2Fledger.example.com%2F",
+                    body
+                );
+                "ledger_token"
+            } else {
+                assert_eq!(
+                    "grant_type=refresh_token&refresh_token=ccc\
+                     &resource=https%3A%2F%2Fapi.example.com%2F",
+                    body
+                );
+                "api_token"
+            };
+
+            Ok(json_response(
+                StatusCode::OK,
+                &format!(
+                    "{{\"access_token\": \"{}\", \"token_type\": \"bearer\"}}",
+                    access_token
+                ),
+            ))
+        });
+    let refresh_token = RefreshToken::new("ccc".to_string());
+
+    let token = client.exchange_refresh_token(&refresh_token).unwrap();
+    assert_eq!("api_token", token.access_token().secret());
+
+    let token = client
+        .with_resources(vec![ResourceUrl::new(
+            Url::parse("https://ledger.example.com/").unwrap(),
+        )])
+        .exchange_refresh_token(&refresh_token)
+        .unwrap();
+    assert_eq!("ledger_token", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_code_with_authorization_details() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct PaymentInitiation {
+        #[serde(rename = "type")]
+        detail_type: String,
+        #[serde(rename = "instructedAmount")]
+        instructed_amount: InstructedAmount,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct InstructedAmount {
+        currency: String,
+        amount: String,
+    }
+
+    let payment = PaymentInitiation {
+        detail_type: "payment_initiation".to_string(),
+        instructed_amount: InstructedAmount {
+            currency: "EUR".to_string(),
+            amount: "123.50".to_string(),
+        },
+    };
+
+    let client = new_client()
+        .add_authorization_detail(AuthorizationDetail::from_typed(&payment).unwrap())
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(
+                Some("authorization_code".to_string()),
+                form_param(&request.body, "grant_type")
+            );
+            assert_eq!(
+                Some(
+                    "[{\"type\":\"payment_initiation\",\
+                     \"instructedAmount\":{\"amount\":\"123.50\",\"currency\":\"EUR\"}}]"
+                        .to_string()
+                ),
+                form_param(&request.body, "authorization_details")
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \
+                 \"authorization_details\": [{\"type\": \"payment_initiation\", \
+                 \"actions\": [\"initiate\"], \"instructedAmount\": \
+                 {\"currency\": \"EUR\", \"amount\": \"123.50\"}}]}",
+            ))
+        });
+
+    let token = client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .unwrap();
+
+    let granted = token.authorization_details().unwrap();
+    assert_eq!(1, granted.len());
+    assert_eq!("payment_initiation", granted[0].detail_type().as_str());
+    assert_eq!(&["initiate".to_string()], granted[0].actions());
+    assert_eq!(payment, granted[0].to_typed::<PaymentInitiation>().unwrap());
+
+    // Tokens without authorization details serialize without the field.
+    let token =
+        BasicTokenResponse::from_json("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+            .unwrap();
+    assert_eq!(None, token.authorization_details());
+    assert_eq!(
+        "{\"access_token\":\"12/34\",\"token_type\":\"bearer\"}",
+        serde_json::to_string(&token).unwrap()
+    );
+}
+
+#[test]
+fn test_exchange_token() {
+    let client = new_client()
+        .add_scope(Scope::new("ignored".to_string()))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Atoken-exchange\
+                 &resource=https%3A%2F%2Fapi.example.com%2F&audience=backend&audience=ledger\
+                 &scope=read+write\
+                 &requested_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Ajwt\
+                 &subject_token=subj\
+                 &subject_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Aaccess_token\
+                 &actor_token=act&actor_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Ajwt",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"eyJ.abc.def\", \
+                 \"issued_token_type\": \"urn:ietf:params:oauth:token-type:jwt\", \
+                 \"token_type\": \"N_A\", \"expires_in\": 60}",
+            ))
+        });
+
+    let request = TokenExchangeRequest::new(
+        SubjectToken::new("subj".to_string()),
+        TokenTypeIdentifier::access_token(),
+    )
+    .set_actor_token(
+        ActorToken::new("act".to_string()),
+        TokenTypeIdentifier::jwt(),
+    )
+    .add_audience(Audience::new("backend".to_string()))
+    .add_audience(Audience::new("ledger".to_string()))
+    .add_resource(ResourceUrl::new(
+        Url::parse("https://api.example.com/").unwrap(),
+    ))
+    .set_requested_token_type(TokenTypeIdentifier::jwt())
+    .add_scope(Scope::new("read".to_string()))
+    .add_scope(Scope::new("write".to_string()));
+
+    let response = client.exchange_token(&request).unwrap();
+
+    assert_eq!(TokenTypeIdentifier::jwt(), *response.issued_token_type());
+    assert_eq!("eyJ.abc.def", response.token().access_token().secret());
+    assert_eq!(
+        BasicTokenType::NotApplicable,
+        *response.token().token_type()
+    );
+    assert_eq!(Some(Duration::from_secs(60)), response.token().expires_in());
+}
+
+#[test]
+fn test_exchange_token_async_with_client_scopes() {
+    let client = new_client()
+        .add_scope(Scope::new("read".to_string()))
+        .set_async_http_client(|request: HttpRequest| {
+            assert_eq!(
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Atoken-exchange\
+                 &scope=read&subject_token=subj\
+                 &subject_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Aid_token",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            future::ready(Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \
+                 \"issued_token_type\": \"urn:ietf:params:oauth:token-type:access_token\", \
+                 \"token_type\": \"bearer\"}",
+            )))
+        });
+
+    let response = block_on(client.exchange_token_async(&TokenExchangeRequest::new(
+        SubjectToken::new("subj".to_string()),
+        TokenTypeIdentifier::id_token(),
+    )))
+    .unwrap();
+
+    assert_eq!(
+        TokenTypeIdentifier::access_token(),
+        *response.issued_token_type()
+    );
+    let token = response.into_token();
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+}
+
+#[cfg(feature = "jwt")]
+const RSA_PRIVATE_KEY_PEM: &str = include_str!("keys/rsa_private_key.pem");
+const EC_PRIVATE_KEY_PEM: &str = include_str!("keys/ec_private_key.pem");
+const CLIENT_CERTIFICATE_PEM: &str = include_str!("keys/client_certificate.pem");
+#[cfg(feature = "jwt")]
+const ED25519_PRIVATE_KEY: &str = "PfpCfZva6Z_vF4x4c6w1EIc-3wQVhoIPy2O-UUC4S3E";
+
+// Splits a JWT into its decoded header and claims, along with the signing input and signature.
+fn decode_jwt(jwt: &str) -> (serde_json::Value, serde_json::Value, String, Vec<u8>) {
+    let parts = jwt.split('.').collect::<Vec<_>>();
+    assert_eq!(3, parts.len());
+    let decode = |part: &str| base64::decode_config(part, base64::URL_SAFE_NO_PAD).unwrap();
+    (
+        serde_json::from_slice(&decode(parts[0])).unwrap(),
+        serde_json::from_slice(&decode(parts[1])).unwrap(),
+        format!("{}.{}", parts[0], parts[1]),
+        decode(parts[2]),
+    )
+}
+
+fn form_param(body: &[u8], name: &str) -> Option<String> {
+    form_urlencoded::parse(body)
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_jwt_bearer_rs256() {
+    let assertion = Arc::new(Mutex::new(None));
+    let assertion_clone = assertion.clone();
+    let client = new_client()
+        .add_scope(Scope::new("read".to_string()))
+        .set_http_client(move |request: HttpRequest| {
+            assert_eq!(
+                Some("urn:ietf:params:oauth:grant-type:jwt-bearer".to_string()),
+                form_param(&request.body, "grant_type")
+            );
+            assert_eq!(Some("read".to_string()), form_param(&request.body, "scope"));
+            *assertion_clone.lock().unwrap() = form_param(&request.body, "assertion");
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let signing_key = RsaSigningKey::rs256_from_pem(RSA_PRIVATE_KEY_PEM)
+        .unwrap()
+        .set_key_id("key-1".to_string());
+    let request = JwtBearerRequest::new(
+        signing_key,
+        "service@example.com".to_string(),
+        "user@example.com".to_string(),
+    )
+    .set_expires_in(Duration::from_secs(60))
+    .add_claim("scope".to_string(), serde_json::Value::from("read"));
+
+    let token = client.exchange_jwt_bearer(&request).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    let assertion = assertion.lock().unwrap().clone().unwrap();
+    let (header, claims, signing_input, signature) = decode_jwt(&assertion);
+    assert_eq!(
+        serde_json::json!({"alg": "RS256", "typ": "JWT", "kid": "key-1"}),
+        header
+    );
+    assert_eq!("service@example.com", claims["iss"]);
+    assert_eq!("user@example.com", claims["sub"]);
+    assert_eq!("http://example.com/token", claims["aud"]);
+    assert_eq!("read", claims["scope"]);
+    assert_eq!(
+        60,
+        claims["exp"].as_u64().unwrap() - claims["iat"].as_u64().unwrap()
+    );
+    assert!(claims["jti"].as_str().unwrap().len() >= 16);
+
+    let public_key = RsaPrivateKey::from_pkcs8_pem(RSA_PRIVATE_KEY_PEM)
+        .unwrap()
+        .to_public_key();
+    rsa::pkcs1v15::VerifyingKey::<Sha256>::new(public_key)
+        .verify(
+            signing_input.as_bytes(),
+            &rsa::pkcs1v15::Signature::try_from(signature.as_slice()).unwrap(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_exchange_jwt_bearer_async_hs256() {
+    let client = new_client().set_async_http_client(|request: HttpRequest| {
+        let assertion = form_param(&request.body, "assertion").unwrap();
+        let (header, claims, signing_input, signature) = decode_jwt(&assertion);
+        assert_eq!(serde_json::json!({"alg": "HS256", "typ": "JWT"}), header);
+        assert_eq!("https://audience.example.com", claims["aud"]);
+        assert_eq!(
+            300,
+            claims["exp"].as_u64().unwrap() - claims["iat"].as_u64().unwrap()
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"shared secret").unwrap();
+        mac.update(signing_input.as_bytes());
+        mac.verify_slice(&signature).unwrap();
+
+        future::ready(Ok(json_response(
+            StatusCode::OK,
+            "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+        )))
+    });
+
+    let request = JwtBearerRequest::new(
+        HmacSigningKey::hs256(b"shared secret"),
+        "aaa".to_string(),
+        "aaa".to_string(),
+    )
+    .set_audience("https://audience.example.com".to_string());
+
+    let token = block_on(client.exchange_jwt_bearer_async(&request)).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_jwt_bearer_with_huge_expires_in() {
+    let client = new_client().set_http_client(|request: HttpRequest| {
+        let assertion = form_param(&request.body, "assertion").unwrap();
+        let (_, claims, _, _) = decode_jwt(&assertion);
+        assert_eq!(u64::MAX, claims["exp"].as_u64().unwrap());
+
+        Ok(json_response(
+            StatusCode::OK,
+            "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+        ))
+    });
+
+    let request = JwtBearerRequest::new(
+        HmacSigningKey::hs256(b"shared secret"),
+        "aaa".to_string(),
+        "aaa".to_string(),
+    )
+    .set_expires_in(Duration::from_secs(u64::MAX));
+
+    let token = client.exchange_jwt_bearer(&request).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_code_with_client_secret_jwt() {
+    let jtis = Arc::new(Mutex::new(Vec::new()));
+    let jtis_clone = jtis.clone();
+    let client = new_client()
+        .set_client_authentication(ClientSecretJwt::new().set_expires_in(Duration::from_secs(60)))
+        .set_http_client(move |request: HttpRequest| {
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(None, form_param(&request.body, "client_secret"));
+            assert_eq!(
+                Some("aaa".to_string()),
+                form_param(&request.body, "client_id")
+            );
+            assert_eq!(
+                Some("urn:ietf:params:oauth:client-assertion-type:jwt-bearer".to_string()),
+                form_param(&request.body, "client_assertion_type")
+            );
+
+            let assertion = form_param(&request.body, "client_assertion").unwrap();
+            let (header, claims, signing_input, signature) = decode_jwt(&assertion);
+            assert_eq!(serde_json::json!({"alg": "HS256", "typ": "JWT"}), header);
+            assert_eq!("aaa", claims["iss"]);
+            assert_eq!("aaa", claims["sub"]);
+            assert_eq!("http://example.com/token", claims["aud"]);
+            assert_eq!(
+                60,
+                claims["exp"].as_u64().unwrap() - claims["iat"].as_u64().unwrap()
+            );
+            jtis_clone
+                .lock()
+                .unwrap()
+                .push(claims["jti"].as_str().unwrap().to_string());
+
+            let mut mac = Hmac::<Sha256>::new_from_slice(b"bbb").unwrap();
+            mac.update(signing_input.as_bytes());
+            mac.verify_slice(&signature).unwrap();
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    for _ in 0..2 {
+        let token = client
+            .exchange_code(AuthorizationCode::new("ccc".to_string()))
+            .unwrap();
+        assert_eq!("12/34", token.access_token().secret());
+    }
+
+    let jtis = jtis.lock().unwrap();
+    assert_eq!(2, jtis.len());
+    assert_ne!(jtis[0], jtis[1]);
+}
+
+#[test]
+fn test_exchange_device_access_token_with_client_secret_jwt() {
+    let assertions = Arc::new(Mutex::new(Vec::new()));
+    let assertions_clone = assertions.clone();
+    let client = new_client()
+        .set_client_authentication(ClientSecretJwt::new())
+        .set_http_client(move |request: HttpRequest| {
+            let mut assertions = assertions_clone.lock().unwrap();
+            assertions.push(form_param(&request.body, "client_assertion").unwrap());
+
+            if assertions.len() < 3 {
+                Ok(json_response(
+                    StatusCode::BAD_REQUEST,
+                    "{\"error\": \"authorization_pending\"}",
+                ))
+            } else {
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+                ))
+            }
+        });
+
+    let token = client
+        .exchange_device_access_token(&device_details(60, 0))
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    // Each poll carries a newly signed assertion.
+    let jtis = assertions
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|assertion| decode_jwt(assertion).1["jti"].as_str().unwrap().to_string())
+        .collect::<HashSet<_>>();
+    assert_eq!(3, jtis.len());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_client_credentials_with_private_key_jwt_es256() {
+    let signing_key = EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM)
+        .unwrap()
+        .set_key_id("key-1".to_string())
+        .set_x509_sha256_thumbprint("dGh1bWJwcmludA".to_string());
+    let client = new_client()
+        .set_client_authentication(PrivateKeyJwt::new(signing_key))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(None, form_param(&request.body, "client_secret"));
+            assert_eq!(
+                Some("urn:ietf:params:oauth:client-assertion-type:jwt-bearer".to_string()),
+                form_param(&request.body, "client_assertion_type")
+            );
+
+            let assertion = form_param(&request.body, "client_assertion").unwrap();
+            let (header, claims, signing_input, signature) = decode_jwt(&assertion);
+            assert_eq!(
+                serde_json::json!({
+                    "alg": "ES256",
+                    "typ": "JWT",
+                    "kid": "key-1",
+                    "x5t#S256": "dGh1bWJwcmludA",
+                }),
+                header
+            );
+            assert_eq!("aaa", claims["iss"]);
+            assert_eq!("aaa", claims["sub"]);
+            assert_eq!("http://example.com/token", claims["aud"]);
+            assert_eq!(
+                300,
+                claims["exp"].as_u64().unwrap() - claims["iat"].as_u64().unwrap()
+            );
+
+            let verifying_key = *p256::ecdsa::SigningKey::from_pkcs8_pem(EC_PRIVATE_KEY_PEM)
+                .unwrap()
+                .verifying_key();
+            verifying_key
+                .verify(
+                    signing_input.as_bytes(),
+                    &p256::ecdsa::Signature::from_slice(&signature).unwrap(),
+                )
+                .unwrap();
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let token = client.exchange_client_credentials().unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_backchannel_access_token_async_with_private_key_jwt() {
+    let assertions = Arc::new(Mutex::new(Vec::new()));
+    let assertions_clone = assertions.clone();
+    let signing_key = EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap();
+    let client = new_client()
+        .set_client_authentication(PrivateKeyJwt::new(signing_key))
+        .set_async_http_client(move |request: HttpRequest| {
+            let mut assertions = assertions_clone.lock().unwrap();
+            assertions.push(form_param(&request.body, "client_assertion").unwrap());
+
+            future::ready(if assertions.len() < 3 {
+                Ok(json_response(
+                    StatusCode::BAD_REQUEST,
+                    "{\"error\": \"authorization_pending\"}",
+                ))
+            } else {
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+                ))
+            })
+        });
+
+    let details =
+        serde_json::from_str("{\"auth_req_id\": \"arid\", \"expires_in\": 60, \"interval\": 0}")
+            .unwrap();
+    let token = block_on(client.exchange_backchannel_access_token_async(&details)).unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    // Each poll carries a newly signed assertion.
+    let jtis = assertions
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|assertion| decode_jwt(assertion).1["jti"].as_str().unwrap().to_string())
+        .collect::<HashSet<_>>();
+    assert_eq!(3, jtis.len());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_code_async_with_private_key_jwt_eddsa_jwk() {
+    let jwk = format!(
+        "{{\"kty\": \"OKP\", \"crv\": \"Ed25519\", \"kid\": \"key-2\", \"d\": \"{}\"}}",
+        ED25519_PRIVATE_KEY
+    );
+    let client = new_client()
+        .set_client_authentication(PrivateKeyJwt::new(
+            Ed25519SigningKey::from_jwk(&jwk).unwrap(),
+        ))
+        .set_async_http_client(|request: HttpRequest| {
+            let assertion = form_param(&request.body, "client_assertion").unwrap();
+            let (header, claims, signing_input, signature) = decode_jwt(&assertion);
+            assert_eq!(
+                serde_json::json!({"alg": "EdDSA", "typ": "JWT", "kid": "key-2"}),
+                header
+            );
+            assert_eq!("http://example.com/token", claims["aud"]);
+
+            let secret =
+                base64::decode_config(ED25519_PRIVATE_KEY, base64::URL_SAFE_NO_PAD).unwrap();
+            let secret = <[u8; 32]>::try_from(secret.as_slice()).unwrap();
+            ed25519_dalek::SigningKey::from_bytes(&secret)
+                .verifying_key()
+                .verify(
+                    signing_input.as_bytes(),
+                    &ed25519_dalek::Signature::from_slice(&signature).unwrap(),
+                )
+                .unwrap();
+
+            future::ready(Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            )))
+        });
+
+    let token =
+        block_on(client.exchange_code_async(AuthorizationCode::new("ccc".to_string()))).unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_refresh_token_with_private_key_jwt_ps256() {
+    let client = new_client()
+        .set_client_authentication(PrivateKeyJwt::new(
+            RsaSigningKey::ps256_from_pem(RSA_PRIVATE_KEY_PEM).unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            let assertion = form_param(&request.body, "client_assertion").unwrap();
+            let (header, _, signing_input, signature) = decode_jwt(&assertion);
+            assert_eq!(serde_json::json!({"alg": "PS256", "typ": "JWT"}), header);
+
+            let public_key = RsaPrivateKey::from_pkcs8_pem(RSA_PRIVATE_KEY_PEM)
+                .unwrap()
+                .to_public_key();
+            rsa::pss::VerifyingKey::<Sha256>::new(public_key)
+                .verify(
+                    signing_input.as_bytes(),
+                    &rsa::pss::Signature::try_from(signature.as_slice()).unwrap(),
+                )
+                .unwrap();
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let token = client
+        .exchange_refresh_token(&RefreshToken::new("tttt".to_string()))
+        .unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_signing_key_from_jwk_with_wrong_key_type() {
+    let jwk = format!(
+        "{{\"kty\": \"OKP\", \"crv\": \"Ed25519\", \"d\": \"{}\"}}",
+        ED25519_PRIVATE_KEY
+    );
+    match EcSigningKey::es256_from_jwk(&jwk).err().unwrap() {
+        JwtSigningError::InvalidKey(error_str) => {
+            assert_eq!(
+                "Expected JWK key type `EC`, but found Some(\"OKP\")",
+                error_str
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_saml2_assertion() {
+    let client = new_client()
+        .set_auth_type(AuthType::RequestBody)
+        .add_scope(Scope::new("read".to_string()))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(
+                // base64url("<saml:Assertion>??</saml:Assertion>")
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Asaml2-bearer\
+                 &assertion=PHNhbWw6QXNzZXJ0aW9uPj8_PC9zYW1sOkFzc2VydGlvbj4\
+                 &scope=read&client_id=aaa&client_secret=bbb",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let token = client
+        .exchange_saml2_assertion(&SamlAssertion::new(
+            "<saml:Assertion>??</saml:Assertion>".to_string(),
+        ))
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[test]
+fn test_exchange_saml2_assertion_async_with_json_error() {
+    let client = new_client().set_async_http_client(|request: HttpRequest| {
+        // base64("aaa:bbb")
+        assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+
+        future::ready(Ok(json_response(
+            StatusCode::BAD_REQUEST,
+            "{\"error\": \"invalid_grant\"}",
+        )))
+    });
+
+    let token = block_on(
+        client.exchange_saml2_assertion_async(&SamlAssertion::new("<saml:Assertion/>".to_string())),
+    );
+
+    match token.err().unwrap() {
+        RequestTokenError::ServerResponse(error) => {
+            assert_eq!(BasicErrorResponseType::InvalidGrant, *error.error());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_custom_grant() {
+    let client = new_client()
+        .set_redirect_url(RedirectUrl::new(
+            Url::parse("http://example.com/redirect").unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!(
+                "grant_type=urn%3Aexample%3Agrant-type%3Aticket&ticket=tttt&scope=read\
+                 &redirect_uri=http%3A%2F%2Fexample.com%2Fredirect",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}",
+            ))
+        });
+
+    let token: BasicTokenResponse = client
+        .exchange_custom_grant::<_, BasicErrorResponseType, _>(
+            &GrantType::new("urn:example:grant-type:ticket".to_string()),
+            &[("ticket", "tttt"), ("scope", "read")],
+        )
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+}
+
+#[test]
+fn test_exchange_custom_grant_async_with_custom_types() {
+    use colorful_extension::*;
+
+    #[derive(Debug, Deserialize)]
+    struct TicketResponse {
+        ticket_status: String,
+    }
+
+    let client = new_client().set_async_http_client(|request: HttpRequest| {
+        let body = String::from_utf8(request.body).unwrap();
+        let response = if body.contains("ticket=good") {
+            json_response(StatusCode::OK, "{\"ticket_status\": \"accepted\"}")
+        } else {
+            json_response(StatusCode::BAD_REQUEST, "{\"error\": \"too_dark\"}")
+        };
+        future::ready(Ok(response))
+    });
+    let grant_type = GrantType::new("urn:example:grant-type:ticket".to_string());
+
+    let response: TicketResponse = block_on(
+        client.exchange_custom_grant_async::<_, ColorfulErrorResponseType, _>(
+            &grant_type,
+            &[("ticket", "good".to_string())],
+        ),
+    )
+    .unwrap();
+    assert_eq!("accepted", response.ticket_status);
+
+    let result = block_on(
+        client.exchange_custom_grant_async::<TicketResponse, ColorfulErrorResponseType, _>(
+            &grant_type,
+            &[("ticket", "bad".to_string())],
+        ),
+    );
+    match result.err().unwrap() {
+        RequestTokenError::ServerResponse(error) => {
+            assert_eq!(ColorfulErrorResponseType::TooDark, *error.error());
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_rsa_signing_key_invalid_pem() {
+    match RsaSigningKey::rs256_from_pem("not a key") {
+        Err(JwtSigningError::InvalidKey(_)) => (),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_http_client_config() {
+    let client = new_client()
+        .set_connect_timeout(Duration::from_secs(2))
+        .set_timeout(Duration::from_secs(10))
+        .set_proxy(
+            HttpProxy::new(Url::parse("http://proxy:3128").unwrap())
+                .set_credentials("user".to_string(), ProxyPassword::new("pass".to_string())),
+        )
+        .add_no_proxy("localhost".to_string())
+        .add_no_proxy(".internal".to_string())
+        .set_http_client(|request: HttpRequest| {
+            let config = &request.config;
+            assert_eq!(Some(Duration::from_secs(2)), config.connect_timeout());
+            assert_eq!(Some(Duration::from_secs(10)), config.timeout());
+
+            let proxy = config.proxy().unwrap();
+            assert_eq!(&Url::parse("http://proxy:3128").unwrap(), proxy.url());
+            let (username, password) = proxy.credentials().unwrap();
+            assert_eq!("user", username);
+            assert_eq!("pass", password.secret());
+            assert_eq!("ProxyPassword([redacted])", format!("{:?}", password));
+
+            assert_eq!(
+                &["localhost".to_string(), ".internal".to_string()],
+                config.no_proxy()
+            );
+
+            Err(HttpClientError::Timeout)
+        });
+
+    let token = client.exchange_client_credentials();
+
+    match token.err().unwrap() {
+        RequestTokenError::Timeout => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_http_client_tls_config() {
+    let client = new_client()
+        .add_root_certificate(CertificatePem::new(b"root ca".to_vec()))
+        .add_pinned_public_key(SpkiSha256::new("pin1".to_string()))
+        .add_pinned_public_key(SpkiSha256::new("pin2".to_string()))
+        .set_client_certificate(
+            CertificatePem::new(b"client cert".to_vec()),
+            PrivateKeyPem::new(b"client key".to_vec()),
+        )
+        .set_http_client(|request: HttpRequest| {
+            let config = &request.config;
+            assert_eq!(
+                &[CertificatePem::new(b"root ca".to_vec())],
+                config.root_certificates()
+            );
+            assert!(config.builtin_root_certificates());
+            assert_eq!(
+                &[
+                    SpkiSha256::new("pin1".to_string()),
+                    SpkiSha256::new("pin2".to_string()),
+                ],
+                config.pinned_public_keys()
+            );
+
+            let (certificate, private_key) = config.client_certificate().unwrap();
+            assert_eq!(b"client cert", certificate.as_slice());
+            assert_eq!(b"client key", private_key.secret().as_slice());
+            assert_eq!("PrivateKeyPem([redacted])", format!("{:?}", private_key));
+
+            Err(HttpClientError::Other("not sent".to_string()))
+        });
+
+    assert!(client.exchange_client_credentials().is_err());
+}
+
+#[test]
+fn test_http_client_without_builtin_root_certificates() {
+    let client = new_client()
+        .set_builtin_root_certificates(false)
+        .set_http_client(|request: HttpRequest| {
+            assert!(!request.config.builtin_root_certificates());
+            assert!(request.config.root_certificates().is_empty());
+
+            Err(HttpClientError::Other("not sent".to_string()))
+        });
+
+    assert!(client.exchange_client_credentials().is_err());
+}
+
+#[test]
+fn test_spki_sha256_from_spki_der() {
+    // SHA-256 digest of the empty string.
+    assert_eq!(
+        "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=",
+        SpkiSha256::from_spki_der(b"").as_str()
+    );
+}
+
+#[test]
+fn test_exchange_code_with_tls_client_auth_and_certificate_bound_token() {
+    let client = new_client()
+        .set_client_certificate(
+            CertificatePem::new(CLIENT_CERTIFICATE_PEM.as_bytes().to_vec()),
+            PrivateKeyPem::new(EC_PRIVATE_KEY_PEM.as_bytes().to_vec()),
+        )
+        .set_client_authentication(TlsClientAuth)
+        .set_mtls_endpoint_aliases(MtlsEndpointAliases::new().set_token_url(TokenUrl::new(
+            Url::parse("https://mtls.example.com/token").unwrap(),
+        )))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!("https://mtls.example.com/token", request.url.as_str());
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(
+                "grant_type=authorization_code&code=ccc&client_id=aaa",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \
+                 \"cnf\": {\"x5t#S256\": \"Uewr7aytiimY5D9eeUKYCkd7svw4IG0IAiuf8nK5V24\"}}",
+            ))
+        });
+
+    let token = client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .unwrap();
+
+    assert_eq!(
+        "Uewr7aytiimY5D9eeUKYCkd7svw4IG0IAiuf8nK5V24",
+        token
+            .confirmation()
+            .unwrap()
+            .certificate_thumbprint()
+            .unwrap()
+            .as_str()
+    );
+    token
+        .verify_certificate_binding(&CertificatePem::new(
+            CLIENT_CERTIFICATE_PEM.as_bytes().to_vec(),
+        ))
+        .unwrap();
+    match token
+        .verify_certificate_binding(&CertificatePem::new(EC_PRIVATE_KEY_PEM.as_bytes().to_vec()))
+        .err()
+        .unwrap()
+    {
+        TokenBindingError::InvalidCertificate(_) => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_exchange_device_code_with_mtls_endpoint_aliases() {
+    let aliases: MtlsEndpointAliases = serde_json::from_str(
+        "{\"device_authorization_endpoint\": \"https://mtls.example.com/device\", \
+         \"introspection_endpoint\": \"https://mtls.example.com/introspect\"}",
+    )
+    .unwrap();
+    let client = new_client()
+        .set_device_authorization_url(DeviceAuthorizationUrl::new(
+            Url::parse("http://example.com/device_authorization").unwrap(),
+        ))
+        .set_client_authentication(SelfSignedTlsClientAuth)
+        .set_mtls_endpoint_aliases(aliases)
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!("https://mtls.example.com/device", request.url.as_str());
+
+            Err(HttpClientError::Other("not sent".to_string()))
+        });
+
+    // No client certificate has been configured.
+    match client.exchange_device_code().err().unwrap() {
+        RequestTokenError::Other(error_str) => assert_eq!(
+            "Client authentication failed: self_signed_tls_client_auth requires a client \
+             certificate",
+            error_str
+        ),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    let client = client.set_client_certificate(
+        CertificatePem::new(CLIENT_CERTIFICATE_PEM.as_bytes().to_vec()),
+        PrivateKeyPem::new(EC_PRIVATE_KEY_PEM.as_bytes().to_vec()),
+    );
+    match client.exchange_device_code().err().unwrap() {
+        RequestTokenError::Request(HttpClientError::Other(error_str)) => {
+            assert_eq!("not sent", error_str)
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    let response: BasicTokenResponse = serde_json::from_str(
+        "{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \
+         \"cnf\": {\"x5t#S256\": \"dGh1bWJwcmludA\"}}",
+    )
+    .unwrap();
+    match response
+        .verify_certificate_binding(&CertificatePem::new(
+            CLIENT_CERTIFICATE_PEM.as_bytes().to_vec(),
+        ))
+        .err()
+        .unwrap()
+    {
+        TokenBindingError::Mismatch => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_code_with_dpop_nonce() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let signing_key = EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap();
+    let dpop_key = DpopKey::new(signing_key).unwrap();
+    // Clones of the key share the nonces provided by the server.
+    let dpop_key_clone = dpop_key.clone();
+    let verifying_key = *p256::ecdsa::SigningKey::from_pkcs8_pem(EC_PRIVATE_KEY_PEM)
+        .unwrap()
+        .verifying_key();
+    let client =
+        new_client()
+            .set_dpop_key(dpop_key_clone)
+            .set_http_client(move |request: HttpRequest| {
+                let proof = request.headers[DPOP].to_str().unwrap();
+                let (header, claims, signing_input, signature) = decode_jwt(proof);
+                assert_eq!("dpop+jwt", header["typ"]);
+                assert_eq!("ES256", header["alg"]);
+                assert_eq!("EC", header["jwk"]["kty"]);
+                assert_eq!("POST", claims["htm"]);
+                assert_eq!("http://example.com/token", claims["htu"]);
+                assert!(claims.get("ath").is_none());
+                verifying_key
+                    .verify(
+                        signing_input.as_bytes(),
+                        &p256::ecdsa::Signature::from_slice(&signature).unwrap(),
+                    )
+                    .unwrap();
+
+                if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                    assert!(claims.get("nonce").is_none());
+
+                    let mut headers = HeaderMap::new();
+                    headers.insert(DPOP_NONCE, HeaderValue::from_static("n1"));
+                    Ok(HttpResponse {
+                        status_code: StatusCode::BAD_REQUEST,
+                        headers,
+                        body: b"{\"error\": \"use_dpop_nonce\"}".to_vec(),
+                    })
+                } else {
+                    assert_eq!("n1", claims["nonce"]);
+
+                    Ok(json_response(
+                        StatusCode::OK,
+                        "{\"access_token\": \"12/34\", \"token_type\": \"DPoP\"}",
+                    ))
+                }
+            });
+
+    let token = client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::DPoP, *token.token_type());
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+    assert_eq!(
+        Some("n1".to_string()),
+        dpop_key.nonce(&Url::parse("http://example.com/other").unwrap())
+    );
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_client_credentials_with_dpop_retries() {
+    let jtis = Arc::new(Mutex::new(Vec::new()));
+    let jtis_clone = jtis.clone();
+    let dpop_key = DpopKey::new(EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap()).unwrap();
+    let client = new_client()
+        .set_dpop_key(dpop_key)
+        .set_retry_policy(RetryPolicy::new(3).set_initial_backoff(Duration::from_millis(1)))
+        .set_http_client(move |request: HttpRequest| {
+            let (_, claims, _, _) = decode_jwt(request.headers[DPOP].to_str().unwrap());
+            let mut jtis = jtis_clone.lock().unwrap();
+            jtis.push(claims["jti"].as_str().unwrap().to_string());
+
+            if jtis.len() == 1 {
+                Ok(retry_response(StatusCode::SERVICE_UNAVAILABLE, Some("0")))
+            } else {
+                Ok(retry_response(StatusCode::OK, None))
+            }
+        });
+
+    let token = client.exchange_client_credentials().unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    let jtis = jtis.lock().unwrap();
+    assert_eq!(2, jtis.len());
+    assert_ne!(jtis[0], jtis[1]);
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_exchange_device_access_token_with_dpop() {
+    let jtis = Arc::new(Mutex::new(Vec::new()));
+    let jtis_clone = jtis.clone();
+    let client = new_client()
+        .set_dpop_key(
+            DpopKey::new(EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap()).unwrap(),
+        )
+        .set_http_client(move |request: HttpRequest| {
+            let (_, claims, _, _) = decode_jwt(request.headers[DPOP].to_str().unwrap());
+            let mut jtis = jtis_clone.lock().unwrap();
+            jtis.push(claims["jti"].as_str().unwrap().to_string());
+
+            if jtis.len() < 3 {
+                Ok(json_response(
+                    StatusCode::BAD_REQUEST,
+                    "{\"error\": \"authorization_pending\"}",
+                ))
+            } else {
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"DPoP\"}",
+                ))
+            }
+        });
+
+    let token = client
+        .exchange_device_access_token(&device_details(60, 0))
+        .unwrap();
+
+    assert_eq!(BasicTokenType::DPoP, *token.token_type());
+    // Each poll carries a newly signed proof.
+    let jtis = jtis.lock().unwrap().iter().cloned().collect::<HashSet<_>>();
+    assert_eq!(3, jtis.len());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_dpop_proofs_only_sent_to_token_endpoint() {
+    let client = new_client()
+        .set_device_authorization_url(DeviceAuthorizationUrl::new(
+            Url::parse("http://example.com/device_authorization").unwrap(),
+        ))
+        .set_introspection_url(IntrospectionUrl::new(
+            Url::parse("http://example.com/introspect").unwrap(),
+        ))
+        .set_dpop_key(
+            DpopKey::new(EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap()).unwrap(),
+        )
+        .set_http_client(|request: HttpRequest| match request.url.path() {
+            "/device_authorization" => {
+                assert_eq!(None, request.headers.get(DPOP));
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"device_code\": \"dcdc\", \"user_code\": \"ABCD-EFGH\", \
+                     \"verification_uri\": \"http://example.com/device\", \"expires_in\": 60, \
+                     \"interval\": 0}",
+                ))
+            }
+            "/token" => {
+                assert!(request.headers.contains_key(DPOP));
+                Ok(json_response(
+                    StatusCode::OK,
+                    "{\"access_token\": \"12/34\", \"token_type\": \"DPoP\"}",
+                ))
+            }
+            "/introspect" => {
+                assert_eq!(None, request.headers.get(DPOP));
+                Ok(json_response(StatusCode::OK, "{\"active\": true}"))
+            }
+            path => panic!("Unexpected path: {}", path),
+        });
+
+    let details = client.exchange_device_code().unwrap();
+    let token = client.exchange_device_access_token(&details).unwrap();
+    assert_eq!(BasicTokenType::DPoP, *token.token_type());
+
+    let response: BasicIntrospectionResponse =
+        client.introspect(token.access_token(), None).unwrap();
+    assert!(response.active());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_dpop_resource_proof() {
+    let secret = base64::decode_config(ED25519_PRIVATE_KEY, base64::URL_SAFE_NO_PAD).unwrap();
+    let secret = <[u8; 32]>::try_from(secret.as_slice()).unwrap();
+    let public_key = ed25519_dalek::SigningKey::from_bytes(&secret).verifying_key();
+    let x = base64::encode_config(public_key.as_bytes(), base64::URL_SAFE_NO_PAD);
+
+    let jwk = format!(
+        "{{\"kty\": \"OKP\", \"crv\": \"Ed25519\", \"d\": \"{}\"}}",
+        ED25519_PRIVATE_KEY
+    );
+    let dpop_key = DpopKey::new(Ed25519SigningKey::from_jwk(&jwk).unwrap()).unwrap();
+    assert_eq!(
+        base64::encode_config(
+            &Sha256::digest(format!(
+                "{{\"crv\":\"Ed25519\",\"kty\":\"OKP\",\"x\":\"{}\"}}",
+                x
+            )),
+            base64::URL_SAFE_NO_PAD
+        ),
+        dpop_key.jwk_thumbprint()
+    );
+
+    let url = Url::parse("https://api.example.com/resource?id=1#top").unwrap();
+    dpop_key.set_nonce(&url, "n2".to_string());
+    let proof = dpop_key
+        .proof(
+            &Method::GET,
+            &url,
+            Some(&AccessToken::new("12/34".to_string())),
+        )
+        .unwrap();
+    let (header, claims, signing_input, signature) = decode_jwt(&proof);
+    assert_eq!(
+        serde_json::json!({
+            "typ": "dpop+jwt",
+            "alg": "EdDSA",
+            "jwk": {"kty": "OKP", "crv": "Ed25519", "x": x},
+        }),
+        header
+    );
+    assert_eq!("GET", claims["htm"]);
+    assert_eq!("https://api.example.com/resource", claims["htu"]);
+    assert_eq!("n2", claims["nonce"]);
+    assert_eq!(
+        base64::encode_config(&Sha256::digest(b"12/34"), base64::URL_SAFE_NO_PAD),
+        claims["ath"]
+    );
+    public_key
+        .verify(
+            signing_input.as_bytes(),
+            &ed25519_dalek::Signature::from_slice(&signature).unwrap(),
+        )
+        .unwrap();
+
+    match DpopKey::new(HmacSigningKey::hs256(b"shared secret"))
+        .err()
+        .unwrap()
+    {
+        JwtSigningError::InvalidKey(error_str) => {
+            assert_eq!("DPoP proofs require an asymmetric key", error_str)
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct TenantIntrospectionFields {
+    tenant: String,
+}
+impl ExtraTokenFields for TenantIntrospectionFields {}
+
+#[test]
+fn test_jwk_thumbprint() {
+    // Example from Section 3.1 of RFC 7638. Members are listed out of lexicographic order, and
+    // those that aren't required (`alg` and `kid`) are ignored.
+    let jwk = serde_json::json!({
+        "kid": "2011-04-29",
+        "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_B\
+              JECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_\
+              FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWh\
+              AI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+        "kty": "RSA",
+        "e": "AQAB",
+        "alg": "RS256",
+    });
+    assert_eq!(
+        "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs",
+        jwk_thumbprint(jwk.as_object().unwrap()).unwrap()
+    );
+
+    let jwk = serde_json::json!({"kty": "oct", "k": "c2VjcmV0"});
+    match jwk_thumbprint(jwk.as_object().unwrap()) {
+        Err(JwtSigningError::InvalidKey(_)) => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_introspect_active_token() {
+    let client = new_client()
+        .set_introspection_url(IntrospectionUrl::new(
+            Url::parse("http://example.com/introspect").unwrap(),
+        ))
+        .add_resource(ResourceUrl::new(
+            Url::parse("https://api.example.com/").unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!("http://example.com/introspect", request.url.as_str());
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!(
+                "token=12%2F34&token_type_hint=access_token",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"active\": true, \"scope\": \"read write\", \"client_id\": \"aaa\", \
+                 \"username\": \"jdoe\", \"token_type\": \"Bearer\", \"exp\": 1419356238, \
+                 \"iat\": 1419350238, \"sub\": \"Z5O3upPC88QrAjx00dis\", \
+                 \"aud\": \"https://api.example.com/\", \"iss\": \"https://server.example.com/\", \
+                 \"jti\": \"5f6a\", \"tenant\": \"acme\"}",
+            ))
+        });
+
+    let response: IntrospectionResponse<TenantIntrospectionFields> = client
+        .introspect(
+            &AccessToken::new("12/34".to_string()),
+            Some(&TokenTypeHint::access_token()),
+        )
+        .unwrap();
+
+    assert!(response.active());
+    assert_eq!(
+        Some(&vec![
+            Scope::new("read".to_string()),
+            Scope::new("write".to_string()),
+        ]),
+        response.scopes()
+    );
+    assert_eq!("aaa", response.client_id().unwrap().as_str());
+    assert_eq!(Some("jdoe"), response.username());
+    assert_eq!(Some("Bearer"), response.token_type());
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1419356238)),
+        response.exp()
+    );
+    assert_eq!(
+        Some(UNIX_EPOCH + Duration::from_secs(1419350238)),
+        response.iat()
+    );
+    assert_eq!(None, response.nbf());
+    assert_eq!(Some("Z5O3upPC88QrAjx00dis"), response.sub());
+    assert_eq!(
+        Some(&vec![Audience::new("https://api.example.com/".to_string())]),
+        response.audiences()
+    );
+    assert_eq!(Some("https://server.example.com/"), response.iss());
+    assert_eq!(Some("5f6a"), response.jti());
+    assert_eq!("acme", response.extra_fields().tenant);
+}
+
+#[test]
+fn test_introspect_token_with_huge_timestamps() {
+    let client = new_client()
+        .set_introspection_url(IntrospectionUrl::new(
+            Url::parse("http://example.com/introspect").unwrap(),
+        ))
+        .set_http_client(|_: HttpRequest| {
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"active\": true, \"exp\": 18446744073709551615, \
+                 \"iat\": 18446744073709551615, \"nbf\": 18446744073709551615}",
+            ))
+        });
+
+    let response: BasicIntrospectionResponse = client
+        .introspect(&AccessToken::new("12/34".to_string()), None)
+        .unwrap();
+
+    assert!(response.active());
+    assert_eq!(None, response.exp());
+    assert_eq!(None, response.iat());
+    assert_eq!(None, response.nbf());
+}
+
+#[test]
+fn test_introspect_async_inactive_token() {
+    let client = new_client()
+        .set_introspection_url(IntrospectionUrl::new(
+            Url::parse("http://example.com/introspect").unwrap(),
+        ))
+        .set_endpoint_client_authentication(Endpoint::Introspection, ClientSecretPost)
+        .set_async_http_client(|request: HttpRequest| {
+            assert_eq!(None, request.headers.get(AUTHORIZATION));
+            assert_eq!(
+                "token=12%2F34&client_id=aaa&client_secret=bbb",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            future::ready(Ok(json_response(StatusCode::OK, "{\"active\": false}")))
+        });
+
+    let response: BasicIntrospectionResponse =
+        block_on(client.introspect_async(&AccessToken::new("12/34".to_string()), None)).unwrap();
+
+    assert!(!response.active());
+    assert_eq!(None, response.scopes());
+    assert_eq!(None, response.audiences());
+
+    let response: BasicIntrospectionResponse =
+        serde_json::from_str("{\"active\": true, \"aud\": [\"a\", \"b\"]}").unwrap();
+    assert_eq!(
+        Some(&vec![
+            Audience::new("a".to_string()),
+            Audience::new("b".to_string()),
+        ]),
+        response.audiences()
+    );
+}
+
+#[test]
+fn test_introspect_refresh_token() {
+    let client = new_client()
+        .set_introspection_url(IntrospectionUrl::new(
+            Url::parse("http://example.com/introspect").unwrap(),
+        ))
+        .set_http_client(|request: HttpRequest| {
+            assert_eq!(
+                "token=ccc&token_type_hint=refresh_token",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"active\": true, \"client_id\": \"aaa\"}",
+            ))
+        });
+
+    let response: BasicIntrospectionResponse = client
+        .introspect(
+            &RefreshToken::new("ccc".to_string()),
+            Some(&TokenTypeHint::refresh_token()),
+        )
+        .unwrap();
+
+    assert!(response.active());
+    assert_eq!("aaa", response.client_id().unwrap().as_str());
+}
+
+#[test]
+fn test_introspect_without_introspection_url() {
+    let client = new_client();
+
+    let response: Result<BasicIntrospectionResponse, _> =
+        client.introspect(&AccessToken::new("12/34".to_string()), None);
+
+    match response.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("introspection_url must not be `None`", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_revoke_refresh_token() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_revocation_url(RevocationUrl::new(
+            Url::parse("http://example.com/revoke").unwrap(),
+        ))
+        .add_resource(ResourceUrl::new(
+            Url::parse("https://api.example.com/").unwrap(),
+        ))
+        .set_retry_policy(RetryPolicy::new(3).set_initial_backoff(Duration::from_millis(1)))
+        .set_http_client(move |request: HttpRequest| {
+            assert_eq!("http://example.com/revoke", request.url.as_str());
+            // base64("aaa:bbb")
+            assert_eq!("Basic YWFhOmJiYg==", request.headers[AUTHORIZATION]);
+            assert_eq!(
+                "token=ccc&token_type_hint=refresh_token",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                Ok(retry_response(StatusCode::SERVICE_UNAVAILABLE, Some("0")))
+            } else {
+                Ok(HttpResponse {
+                    status_code: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body: Vec::new(),
+                })
+            }
+        });
+
+    client
+        .revoke_token(&RefreshToken::new("ccc".to_string()))
+        .unwrap();
+
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_revoke_token_retries_without_retry_policy() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_revocation_url(RevocationUrl::new(
+            Url::parse("http://example.com/revoke").unwrap(),
+        ))
+        .set_http_client(move |_: HttpRequest| {
+            if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                Ok(retry_response(StatusCode::SERVICE_UNAVAILABLE, Some("0")))
+            } else {
+                Ok(HttpResponse {
+                    status_code: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body: Vec::new(),
+                })
+            }
+        });
+
+    client
+        .revoke_token(&AccessToken::new("12/34".to_string()))
+        .unwrap();
+
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_revoke_token_service_unavailable() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_revocation_url(RevocationUrl::new(
+            Url::parse("http://example.com/revoke").unwrap(),
+        ))
+        .set_async_http_client(move |_: HttpRequest| {
+            attempts_clone.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok(retry_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Some("120"),
+            )))
+        });
+
+    // The requested delay exceeds the default maximum, so the caller is left to retry later.
+    let result = block_on(client.revoke_token_async(&AccessToken::new("12/34".to_string())));
+
+    match result.err().unwrap() {
+        RequestTokenError::ServiceUnavailable(retry_after) => {
+            assert_eq!(Some(Duration::from_secs(120)), retry_after);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert_eq!(1, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_revoke_access_token_async_unsupported_token_type() {
+    let client = new_client()
+        .set_revocation_url(RevocationUrl::new(
+            Url::parse("http://example.com/revoke").unwrap(),
+        ))
+        .set_async_http_client(|request: HttpRequest| {
+            assert_eq!(
+                "token=12%2F34&token_type_hint=access_token",
+                String::from_utf8(request.body).unwrap()
+            );
+
+            future::ready(Ok(json_response(
+                StatusCode::BAD_REQUEST,
+                "{\"error\": \"unsupported_token_type\", \
+                 \"error_description\": \"Access tokens cannot be revoked\"}",
+            )))
+        });
+
+    let result = block_on(client.revoke_token_async(&AccessToken::new("12/34".to_string())));
+
+    match result.err().unwrap() {
+        RequestTokenError::ServerResponse(error_response) => {
+            assert_eq!(
+                RevocationErrorResponseType::UnsupportedTokenType,
+                *error_response.error()
+            );
+            assert_eq!(
+                Some(&"Access tokens cannot be revoked".to_string()),
+                error_response.error_description()
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    let error_response: ErrorResponse<RevocationErrorResponseType> =
+        serde_json::from_str("{\"error\": \"invalid_client\"}").unwrap();
+    assert_eq!(
+        RevocationErrorResponseType::Basic(BasicErrorResponseType::InvalidClient),
+        *error_response.error()
+    );
+    assert_eq!("invalid_client", error_response.error().to_string());
+}
+
+#[test]
+fn test_revoke_token_without_revocation_url() {
+    let result = new_client().revoke_token(&AccessToken::new("12/34".to_string()));
+
+    match result.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("revocation_url must not be `None`", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+fn retry_response(status_code: StatusCode, retry_after: Option<&'static str>) -> HttpResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(retry_after) = retry_after {
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(retry_after));
+    }
+    let body = if status_code == StatusCode::OK {
+        b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}".to_vec()
+    } else {
+        Vec::new()
+    };
+    HttpResponse {
+        status_code,
+        headers,
+        body,
+    }
+}
+
+fn new_retry_client(responses: Vec<HttpResponse>) -> (BasicClient, Arc<AtomicUsize>) {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_retry_policy(RetryPolicy::new(3).set_initial_backoff(Duration::from_millis(1)))
+        .set_http_client(move |_: HttpRequest| {
+            let attempt = attempts_clone.fetch_add(1, Ordering::SeqCst);
+            Ok(responses[attempt.min(responses.len() - 1)].clone())
+        });
+    (client, attempts)
+}
+
+#[test]
+fn test_exchange_client_credentials_with_retries() {
+    let (client, attempts) = new_retry_client(vec![
+        retry_response(StatusCode::SERVICE_UNAVAILABLE, None),
+        retry_response(StatusCode::TOO_MANY_REQUESTS, Some("0")),
+        retry_response(StatusCode::OK, None),
+    ]);
+
+    let token = client.exchange_client_credentials().unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(3, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_client_credentials_with_retries_after_timeout() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_retry_policy(RetryPolicy::new(3).set_initial_backoff(Duration::from_millis(1)))
+        .set_http_client(move |_: HttpRequest| {
+            if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(HttpClientError::Timeout)
+            } else {
+                Ok(retry_response(StatusCode::OK, None))
+            }
+        });
+
+    let token = client.exchange_client_credentials().unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_refresh_token_with_retries_exhausted() {
+    let (client, attempts) =
+        new_retry_client(vec![retry_response(StatusCode::SERVICE_UNAVAILABLE, None)]);
+
+    let token = client.exchange_refresh_token(&RefreshToken::new("ccc".to_string()));
+
+    match token.err().unwrap() {
+        RequestTokenError::Other(error_str) => {
+            assert_eq!("Server returned empty error response", error_str);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert_eq!(3, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_client_credentials_with_long_retry_after() {
+    let (client, attempts) = new_retry_client(vec![
+        retry_response(StatusCode::TOO_MANY_REQUESTS, Some("3600")),
+        retry_response(StatusCode::OK, None),
+    ]);
+
+    assert!(client.exchange_client_credentials().is_err());
+    assert_eq!(1, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_client_credentials_with_retry_after_exceeding_max_backoff() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_retry_policy(
+            RetryPolicy::new(2)
+                .set_max_backoff(Duration::from_millis(10))
+                .set_max_retry_after(Duration::from_secs(1)),
+        )
+        .set_http_client(move |_: HttpRequest| {
+            if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                Ok(retry_response(StatusCode::SERVICE_UNAVAILABLE, Some("1")))
+            } else {
+                Ok(retry_response(StatusCode::OK, None))
+            }
+        });
+
+    let token = client.exchange_client_credentials().unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_exchange_code_without_retries() {
+    let (client, attempts) = new_retry_client(vec![
+        retry_response(StatusCode::SERVICE_UNAVAILABLE, None),
+        retry_response(StatusCode::OK, None),
+    ]);
+
+    assert!(client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .is_err());
+    assert_eq!(1, attempts.load(Ordering::SeqCst));
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<String>>,
+}
+
+impl HttpObserver for RecordingObserver {
+    fn on_request(&self, request: &HttpRequest) {
+        self.events.lock().unwrap().push(format!(
+            "request: {:?} {}",
+            request.headers.get(AUTHORIZATION),
+            String::from_utf8_lossy(&request.body)
+        ));
+    }
+
+    fn on_response(&self, response: &HttpResponse) {
+        self.events.lock().unwrap().push(format!(
+            "response: {} {}",
+            response.status_code.as_u16(),
+            String::from_utf8_lossy(&response.body)
+        ));
+    }
+
+    fn on_error(&self, error: &HttpClientError) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("error: {}", error));
+    }
+}
+
+#[test]
+fn test_exchange_code_with_http_observer() {
+    let observer = Arc::new(RecordingObserver::default());
+    let client = new_client()
+        .add_http_observer(observer.clone())
+        .set_http_client(|_: HttpRequest| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Ok(HttpResponse {
+                status_code: StatusCode::OK,
+                headers,
+                body: b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\", \
+                       \"refresh_token\": \"foobar\"}"
+                    .to_vec(),
+            })
+        });
+
+    let token = client
+        .exchange_code_extension(
+            AuthorizationCode::new("ccc".to_string()),
+            &[("code_verifier", "ddd"), ("foo", "bar")],
+        )
+        .unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    assert_eq!(
+        vec![
+            "request: Some(\"[redacted]\") grant_type=authorization_code&code=[redacted]&\
+             code_verifier=[redacted]&foo=bar"
+                .to_string(),
+            "response: 200 {\"access_token\":\"[redacted]\",\"refresh_token\":\"[redacted]\",\
+             \"token_type\":\"bearer\"}"
+                .to_string(),
+        ],
+        *observer.events.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_exchange_client_credentials_with_http_observer_error() {
+    let observer = Arc::new(RecordingObserver::default());
+    let client = new_client()
+        .set_auth_type(AuthType::RequestBody)
+        .add_http_observer(observer.clone())
+        .set_async_http_client(|_: HttpRequest| {
+            future::ready(Err(HttpClientError::Other(
+                "connection refused".to_string(),
+            )))
+        });
+
+    assert!(block_on(client.exchange_client_credentials_async()).is_err());
+    assert_eq!(
+        vec![
+            "request: None grant_type=client_credentials&client_id=aaa&\
+             client_secret=[redacted]"
+                .to_string(),
+            "error: Other error: connection refused".to_string(),
+        ],
+        *observer.events.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_http_request_debug_redaction() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_static("Basic YWFhOmJiYg=="),
+    );
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-www-form-urlencoded"),
+    );
+    let request = HttpRequest {
+        url: Url::parse("http://example.com/token").unwrap(),
+        method: Method::POST,
+        headers,
+        body: b"grant_type=password&username=user&password=secret".to_vec(),
+        config: HttpClientConfig::default(),
+    };
+
+    let debug = format!("{:?}", request);
+    assert!(debug.contains("username=user&password=[redacted]"));
+    assert!(!debug.contains("secret"));
+    assert!(!debug.contains("YWFhOmJiYg=="));
+
+    let debug = Arc::new(Mutex::new(String::new()));
+    let debug_clone = debug.clone();
+    let client = new_client()
+        .set_proxy(
+            HttpProxy::new(Url::parse("http://proxy.example.com:3128").unwrap()).set_credentials(
+                "proxy-user".to_string(),
+                ProxyPassword::new("proxy-secret".to_string()),
+            ),
+        )
+        .set_client_certificate(
+            CertificatePem::new(b"client cert".to_vec()),
+            PrivateKeyPem::new(b"client key secret".to_vec()),
+        )
+        .set_http_client(move |request: HttpRequest| {
+            *debug_clone.lock().unwrap() = format!("{:?}", request);
+            Err(HttpClientError::Other("not sent".to_string()))
+        });
+    let request = UmaGrantRequest::new(PermissionTicket::new("tttt".to_string()));
+    assert!(client.exchange_uma_ticket(&request).is_err());
+
+    let debug = debug.lock().unwrap();
+    assert!(debug.contains("ticket=[redacted]"));
+    assert!(!debug.contains("tttt"));
+    assert!(debug.contains("PrivateKeyPem([redacted])"));
+    assert!(debug.contains("ProxyPassword([redacted])"));
+    assert!(!debug.contains("secret"));
+}
+
+#[test]
+fn test_cassette_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("oauth2-cassette-{}.json", std::process::id()));
+
+    let recording_client = new_client().set_http_client(RecordingHttpClient::new(
+        |_: HttpRequest| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Ok(HttpResponse {
+                status_code: StatusCode::OK,
+                headers,
+                body: b"{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}".to_vec(),
+            })
+        },
+        &path,
+    ));
+    let token = recording_client
+        .exchange_code(AuthorizationCode::new("ccc".to_string()))
+        .unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    assert!(cassette.contains("grant_type=authorization_code&code=[redacted]"));
+    assert!(!cassette.contains("YWFhOmJiYg=="));
+    assert!(!cassette.contains("12/34"));
+
+    let replay = ReplayHttpClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(1, replay.remaining());
+
+    // The extra parameter is missing from the recorded request.
+    let mismatched = new_client()
+        .set_http_client(replay.clone())
+        .exchange_code_extension(AuthorizationCode::new("ccc".to_string()), &[("foo", "bar")]);
+    match mismatched.err().unwrap() {
+        RequestTokenError::Request(HttpClientError::Other(error_str)) => {
+            assert!(error_str.starts_with("Request does not match recorded interaction 0"));
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    // Recorded secrets are ignored when matching requests.
+    let token = block_on(
+        new_client()
+            .set_async_http_client(replay.clone())
+            .exchange_code_async(AuthorizationCode::new("other".to_string())),
+    )
+    .unwrap();
+    assert_eq!("[redacted]", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+    assert_eq!(0, replay.remaining());
+}
+
+#[cfg(feature = "jwt")]
+#[test]
+fn test_cassette_record_and_replay_with_dpop() {
+    let path =
+        std::env::temp_dir().join(format!("oauth2-cassette-dpop-{}.json", std::process::id()));
+    let new_dpop_client = || {
+        new_client().set_dpop_key(
+            DpopKey::new(EcSigningKey::es256_from_pem(EC_PRIVATE_KEY_PEM).unwrap()).unwrap(),
+        )
+    };
+
+    let recording_client = new_dpop_client().set_http_client(RecordingHttpClient::new(
+        |request: HttpRequest| {
+            assert!(request.headers[DPOP].to_str().unwrap().starts_with("eyJ"));
+            Ok(json_response(
+                StatusCode::OK,
+                "{\"access_token\": \"12/34\", \"token_type\": \"DPoP\"}",
+            ))
+        },
+        &path,
+    ));
+    recording_client.exchange_client_credentials().unwrap();
+
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    assert!(cassette.contains("dpop"));
+    assert!(!cassette.contains("eyJ"));
+
+    let replay = ReplayHttpClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // Each request carries a new proof, which is ignored when matching requests.
+    let token = new_dpop_client()
+        .set_http_client(replay.clone())
+        .exchange_client_credentials()
+        .unwrap();
+    assert_eq!(BasicTokenType::DPoP, *token.token_type());
+    assert_eq!(0, replay.remaining());
+}
+
+#[test]
+fn test_exchange_refresh_token_async_with_retries() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+    let client = new_client()
+        .set_retry_policy(RetryPolicy::new(3).set_initial_backoff(Duration::from_millis(1)))
+        .set_async_http_client(move |_: HttpRequest| {
+            let status_code = if attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                StatusCode::BAD_GATEWAY
+            } else {
+                StatusCode::OK
+            };
+            future::ready(Ok(retry_response(status_code, None)))
+        });
+
+    let token =
+        block_on(client.exchange_refresh_token_async(&RefreshToken::new("ccc".to_string())))
+            .unwrap();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(2, attempts.load(Ordering::SeqCst));
+}
+
+#[cfg(feature = "curl")]
+#[test]
+fn test_exchange_code_with_curl_timeout() {
+    // The listener accepts connections (via the kernel's backlog) but never responds.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = BasicClient::new(
+        ClientId::new("aaa".to_string()),
+        Some(ClientSecret::new("bbb".to_string())),
+        AuthUrl::new(Url::parse("http://example.com/auth").unwrap()),
+        Some(TokenUrl::new(
+            Url::parse(&format!("http://{}/token", listener.local_addr().unwrap())).unwrap(),
+        )),
+    )
+    .set_timeout(Duration::from_millis(200));
+
+    let token = client.exchange_code(AuthorizationCode::new("ccc".to_string()));
+
+    match token.err().unwrap() {
+        RequestTokenError::Timeout => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+// Spawns an HTTP/1.1 server that responds to each request with a token, keeping connections
+// alive. Returns the server's address and a counter of accepted connections.
+#[cfg(feature = "curl")]
+fn spawn_keep_alive_server() -> (std::net::SocketAddr, Arc<AtomicUsize>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let connections_clone = connections.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            connections_clone.fetch_add(1, Ordering::SeqCst);
+            let stream = stream.unwrap();
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut writer = stream;
+                loop {
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                            return;
+                        }
+                        let line = line.trim_end().to_lowercase();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some(value) = line.strip_prefix("content-length:") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    let response = "{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}";
+                    write!(
+                        writer,
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                         content-length: {}\r\n\r\n{}",
+                        response.len(),
+                        response
+                    )
+                    .unwrap();
+                }
+            });
+        }
+    });
+
+    (addr, connections)
+}
+
+#[cfg(feature = "curl")]
+#[test]
+fn test_exchange_client_credentials_with_curl_connection_reuse() {
+    let (addr, connections) = spawn_keep_alive_server();
+    let client = BasicClient::new(
+        ClientId::new("aaa".to_string()),
+        Some(ClientSecret::new("bbb".to_string())),
+        AuthUrl::new(Url::parse("http://example.com/auth").unwrap()),
+        Some(TokenUrl::new(
+            Url::parse(&format!("http://{}/token", addr)).unwrap(),
+        )),
+    );
+
+    for _ in 0..3 {
+        let token = client.clone().exchange_client_credentials().unwrap();
+        assert_eq!("12/34", token.access_token().secret());
+    }
+
+    assert_eq!(1, connections.load(Ordering::SeqCst));
+}
+
+#[cfg(feature = "curl")]
+#[test]
+fn test_exchange_client_credentials_with_curl_after_connection_closed() {
+    let mock = mock("POST", "/token")
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .expect(2)
+        .create();
+
+    // The mock server closes each connection after responding, so the second request is resent
+    // over a new connection after the pooled one fails.
+    let client = new_mock_client();
+    for _ in 0..2 {
+        let token = client.exchange_client_credentials().unwrap();
+        assert_eq!("12/34", token.access_token().secret());
+    }
+
+    mock.assert();
+}
+
+#[cfg(feature = "curl")]
+#[test]
+fn test_exchange_client_credentials_with_curl_root_certificates() {
+    let mock = mock("POST", "/token")
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .expect(2)
+        .create();
+
+    // The root certificate extends the system's CA bundle unless the built-in root certificates
+    // are disabled.
+    let client = new_mock_client().add_root_certificate(CertificatePem::new(
+        CLIENT_CERTIFICATE_PEM.as_bytes().to_vec(),
+    ));
+    let token = client.exchange_client_credentials().unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    // Requests fail before being sent if the system's CA bundle can't be read.
+    let ssl_cert_file = std::env::var_os("SSL_CERT_FILE");
+    std::env::set_var("SSL_CERT_FILE", "/nonexistent/ca-bundle.crt");
+    let token = client.exchange_client_credentials();
+    match ssl_cert_file {
+        Some(ssl_cert_file) => std::env::set_var("SSL_CERT_FILE", ssl_cert_file),
+        None => std::env::remove_var("SSL_CERT_FILE"),
+    }
+    match token.err().unwrap() {
+        RequestTokenError::Request(HttpClientError::Other(error_str)) => {
+            assert!(error_str.starts_with(
+                "Failed to read the system CA bundle `/nonexistent/ca-bundle.crt`"
+            ));
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    let client = client.set_builtin_root_certificates(false);
+    let token = client.exchange_client_credentials().unwrap();
+    assert_eq!("12/34", token.access_token().secret());
+
+    mock.assert();
+}
+
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_client_credentials_with_reqwest() {
+    let mock = mock("POST", "/token")
+        .match_header("Accept", "application/json")
+        // base64(urlencode("aaa/;&") + ":" + urlencode("bbb/;&"))
+        .match_header(
+            "Authorization",
+            "Basic YWFhJTJGJTNCJTI2OmJiYiUyRiUzQiUyNg==",
+        )
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .create();
+
+    let client = new_mock_client_with_unsafe_chars()
+        .set_http_client(oauth2::reqwest::ReqwestHttpClient::new());
+    let token = client.exchange_client_credentials().unwrap();
+
+    mock.assert();
+
+    assert_eq!("12/34", token.access_token().secret());
+    assert_eq!(BasicTokenType::Bearer, *token.token_type());
+}
+
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_client_credentials_with_reqwest_no_proxy() {
+    let mock = mock("POST", "/token")
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .create();
+
+    // Nothing listens on the proxy's port, so requests sent via the proxy fail.
+    let proxy_port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let client = new_mock_client()
+        .set_proxy(HttpProxy::new(
+            Url::parse(&format!("http://127.0.0.1:{}", proxy_port)).unwrap(),
+        ))
+        .add_no_proxy("127.0.0.1".to_string())
+        .set_http_client(oauth2::reqwest::ReqwestHttpClient::new());
+    let token = client.exchange_client_credentials().unwrap();
+
+    mock.assert();
+
+    assert_eq!("12/34", token.access_token().secret());
+}
+
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_client_credentials_with_provided_reqwest_client() {
+    let mock = mock("POST", "/token")
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .create();
+
+    let client = new_mock_client().set_http_client(oauth2::reqwest::ReqwestHttpClient::from(
+        reqwest::blocking::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap(),
+    ));
+    let token = client.exchange_client_credentials().unwrap();
+
+    mock.assert();
+
+    assert_eq!("12/34", token.access_token().secret());
+}
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_code_with_reqwest_timeout() {
+    // The listener accepts connections (via the kernel's backlog) but never responds.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = BasicClient::new(
+        ClientId::new("aaa".to_string()),
+        Some(ClientSecret::new("bbb".to_string())),
+        AuthUrl::new(Url::parse("http://example.com/auth").unwrap()),
+        Some(TokenUrl::new(
+            Url::parse(&format!("http://{}/token", listener.local_addr().unwrap())).unwrap(),
+        )),
+    )
+    .set_timeout(Duration::from_millis(200))
+    .set_http_client(oauth2::reqwest::ReqwestHttpClient::new());
+
+    let token = client.exchange_code(AuthorizationCode::new("ccc".to_string()));
+
+    match token.err().unwrap() {
+        RequestTokenError::Timeout => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_code_with_reqwest_pinned_public_key() {
+    let client = new_client()
+        .add_pinned_public_key(SpkiSha256::new("pin".to_string()))
+        .set_http_client(oauth2::reqwest::ReqwestHttpClient::new());
+
+    let token = client.exchange_code(AuthorizationCode::new("ccc".to_string()));
+
+    match token.err().unwrap() {
+        RequestTokenError::Request(HttpClientError::Other(error_str)) => {
+            assert_eq!(
+                "Public key pinning is not supported by the reqwest HTTP clients",
+                error_str
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[cfg(feature = "reqwest")]
+#[test]
+fn test_exchange_refresh_token_with_async_reqwest() {
+    let mock = mock("POST", "/token")
+        .match_header("Accept", "application/json")
+        .match_body("grant_type=refresh_token&refresh_token=ccc&client_id=aaa&client_secret=bbb")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body("{\"error\": \"invalid_grant\"}")
+        .create();
+
+    let client = new_mock_client()
+        .set_auth_type(oauth2::AuthType::RequestBody)
+        .set_async_http_client(oauth2::reqwest::async_http_client);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let token = runtime
+        .block_on(client.exchange_refresh_token_async(&RefreshToken::new("ccc".to_string())));
+
+    mock.assert();
+
+    match token.err().unwrap() {
+        RequestTokenError::ServerResponse(error_response) => {
+            assert_eq!(
+                BasicErrorResponseType::InvalidGrant,
+                *error_response.error()
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
 mod colorful_extension {
     extern crate serde_json;
 
@@ -918,6 +4160,7 @@ mod colorful_extension {
         TokenResponse<ColorfulFields, ColorfulTokenType, StringScopeField>;
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_extension_successful_with_minimal_json_response() {
     use colorful_extension::*;
@@ -962,6 +4205,7 @@ fn test_extension_successful_with_minimal_json_response() {
     assert_eq!(token, deserialized_token);
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_extension_successful_with_complete_json_response() {
     use colorful_extension::*;
@@ -1019,6 +4263,7 @@ fn test_extension_successful_with_complete_json_response() {
     assert_eq!(token, deserialized_token);
 }
 
+#[cfg(feature = "curl")]
 #[test]
 fn test_extension_with_simple_json_error() {
     use colorful_extension::*;
@@ -0,0 +1,68 @@
+// These tests modify the proxy environment variables, which are shared by all threads, so they
+// run in a separate process from the tests in lib.rs.
+#![cfg(feature = "reqwest")]
+
+extern crate mockito;
+extern crate oauth2;
+extern crate url;
+
+use mockito::{mock, server_url};
+use std::env;
+use std::net::TcpListener;
+use url::Url;
+
+use oauth2::basic::*;
+use oauth2::prelude::*;
+use oauth2::reqwest::ReqwestHttpClient;
+use oauth2::*;
+
+#[test]
+fn test_exchange_client_credentials_with_reqwest_environment_proxy_and_no_proxy() {
+    // Nothing listens on the proxy's port, so requests sent via the proxy fail.
+    let proxy_port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    for name in &[
+        "http_proxy",
+        "HTTP_PROXY",
+        "https_proxy",
+        "HTTPS_PROXY",
+        "all_proxy",
+        "ALL_PROXY",
+        "no_proxy",
+        "NO_PROXY",
+    ] {
+        env::remove_var(name);
+    }
+    env::set_var("http_proxy", format!("http://127.0.0.1:{}", proxy_port));
+
+    let mock = mock("POST", "/token")
+        .match_body("grant_type=client_credentials")
+        .with_header("content-type", "application/json")
+        .with_body("{\"access_token\": \"12/34\", \"token_type\": \"bearer\"}")
+        .create();
+
+    let client = BasicClient::new(
+        ClientId::new("aaa".to_string()),
+        Some(ClientSecret::new("bbb".to_string())),
+        AuthUrl::new(Url::parse("http://example.com/auth").unwrap()),
+        Some(TokenUrl::new(
+            Url::parse(&(server_url().to_string() + "/token")).unwrap(),
+        )),
+    )
+    .set_http_client(ReqwestHttpClient::new());
+
+    // The environment proxy applies by default.
+    assert!(client.exchange_client_credentials().is_err());
+
+    let token = client
+        .add_no_proxy("127.0.0.1".to_string())
+        .exchange_client_credentials()
+        .unwrap();
+
+    mock.assert();
+
+    assert_eq!("12/34", token.access_token().secret());
+}